@@ -0,0 +1,22 @@
+//! Read one position per line on stdin, print `<pit> <next position>` for
+//! each. Suited to shell-driven matches.
+
+use std::io::{self, BufRead};
+
+use bot::cli::best_move_from_fen;
+use bot::{RandomEvaluator, SearchConfig};
+
+fn main() {
+    let eval = RandomEvaluator::default();
+    let cfg = SearchConfig {
+        simulations: 20_000,
+        ..Default::default()
+    };
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("read stdin");
+        match best_move_from_fen(line.trim(), cfg, &eval) {
+            Ok((pit, next)) => println!("{pit} {next}"),
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+}
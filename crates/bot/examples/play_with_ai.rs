@@ -5,18 +5,18 @@ use std::io::{self, Write};
 use bot::{RandomEvaluator, SearchConfig, mcts_search};
 use mancala::{Outcome, Player, State};
 
-fn mcts_pick(state: &State, sims: u32) -> Option<usize> {
+fn mcts_pick(state: &State, sims: u32) -> Option<(usize, State)> {
     let eval = RandomEvaluator::default();
     let cfg = SearchConfig {
         simulations: sims,
         c_puct: 1.2,
+        ..Default::default()
     };
-    mcts_search(state, cfg, &eval).chosen_action
+    let report = mcts_search(state, cfg, &eval);
+    report.chosen_action.zip(report.chosen_state)
 }
 
 fn main() {
-    let mut s = State::new();
-
     // Choose sides
     println!("== Play vs AI ==");
     println!("Choose your side: A or B (default: A)");
@@ -29,11 +29,23 @@ fn main() {
         _ => Player::A,
     };
     let ai = you.opponent();
+
+    println!("Move first? y or n (default: y)");
+    print!("> ");
+    io::stdout().flush().ok();
+    buf.clear();
+    io::stdin().read_line(&mut buf).ok();
+    let first = match buf.trim() {
+        "N" | "n" => ai,
+        _ => you,
+    };
+    let mut s = State::new_with_mover(first);
     let sims_per_move = 50000;
 
     println!("You are {you}. AI is {ai}.");
     println!("{s}");
 
+    let mut ai_chain = 0;
     while !s.is_terminal() {
         if s.current_player() == you {
             // Human turn
@@ -46,44 +58,56 @@ fn main() {
                     io::stdout().flush().ok();
                     buf.clear();
                     io::stdin().read_line(&mut buf).ok();
-                    if let Ok(i) = buf.trim().parse::<usize>()
-                        && let Some(ns) = s.child_after_move(i)
-                    {
-                        s = ns;
-                        break;
+                    let Ok(i) = buf.trim().parse::<usize>() else {
+                        println!("Invalid. Try again.");
+                        continue;
+                    };
+                    let again = s.move_gives_extra_turn(i);
+                    match s.try_child_after_move(i) {
+                        Ok(ns) => {
+                            s = ns;
+                            if again && !s.is_terminal() {
+                                println!("Last stone in your store: you get another turn!");
+                            }
+                            break;
+                        }
+                        Err(e) => println!("Invalid ({e}). Try again."),
                     }
-                    println!("Invalid. Try again.");
                 }
             }
             println!("{s}");
         } else {
             // AI turn
-            let Some(a) = mcts_pick(&s, sims_per_move) else {
+            let Some((a, next)) = mcts_pick(&s, sims_per_move) else {
                 println!("AI has no legal move. Skipping…");
                 continue;
             };
-            println!("AI ({ai}) plays pit index {a}");
-            s = s.child_after_move(a).expect("AI chose legal move");
+            // number the moves when the AI chains extra turns
+            if ai_chain > 0 {
+                println!(
+                    "AI ({ai}) plays pit index {a} (move {} this turn)",
+                    ai_chain + 1
+                );
+            } else {
+                println!("AI ({ai}) plays pit index {a}");
+            }
+            let again = s.move_gives_extra_turn(a);
+            s = next;
             println!("{s}");
+            if again && !s.is_terminal() {
+                println!("AI ({ai}) gets another turn.");
+                ai_chain += 1;
+            } else {
+                ai_chain = 0;
+            }
         }
     }
 
+    let (a, b) = s.stores();
     match s.outcome() {
-        Outcome::Win(p) if p == you => println!(
-            "You win!  score A={}, B={}",
-            s.store(Player::A),
-            s.store(Player::B)
-        ),
-        Outcome::Win(_) => println!(
-            "AI wins.   score A={}, B={}",
-            s.store(Player::A),
-            s.store(Player::B)
-        ),
-        Outcome::Draw => println!(
-            "Draw.      score A={}, B={}",
-            s.store(Player::A),
-            s.store(Player::B)
-        ),
+        Outcome::Win(p) if p == you => println!("You win!  score A={a}, B={b}"),
+        Outcome::Win(_) => println!("AI wins.   score A={a}, B={b}"),
+        Outcome::Draw => println!("Draw.      score A={a}, B={b}"),
         Outcome::Ongoing => println!("Ongoing? (should not happen)"),
     }
 }
@@ -0,0 +1,418 @@
+//! Depth-limited alpha-beta (negamax) search, with an anytime driver.
+//!
+//! Scores are in evaluator units: `[-1, 1]` for the side to move, exact
+//! `±1`/`0` at terminal positions. Extra turns keep the window unflipped.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use mancala::{Outcome, PITS_PER_SIDE, Player, State};
+
+use super::evaluator::Evaluator;
+
+/// Knobs for [`search_with`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AlphaBetaConfig {
+    /// Order moves: transposition-table best move, then killer moves for
+    /// the ply, then the rest by immediate gain and history. Off searches in
+    /// ascending pit order.
+    pub move_ordering: bool,
+    /// Fail-soft: a node may return a score outside its `(alpha, beta)`
+    /// window, a tighter bound for the caller. Off (fail-hard) clamps every
+    /// returned score into the window. The root's window is unbounded, so
+    /// the root score and move are the same either way. The table keeps
+    /// only best moves, never bounds, so it is unaffected.
+    pub fail_soft: bool,
+}
+
+impl Default for AlphaBetaConfig {
+    fn default() -> Self {
+        Self {
+            move_ordering: true,
+            fail_soft: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AlphaBetaResult {
+    pub best_move: Option<usize>,
+    pub score: f32,
+    /// Positions visited, including the root.
+    pub nodes: u64,
+}
+
+/// Best move and score from a full-width search to `depth` plies.
+pub fn search<E: Evaluator + ?Sized>(state: &State, depth: u32, eval: &E) -> (Option<usize>, f32) {
+    let r = search_with(state, depth, eval, AlphaBetaConfig::default());
+    (r.best_move, r.score)
+}
+
+/// [`search`] with explicit options and node accounting.
+pub fn search_with<E: Evaluator + ?Sized>(
+    state: &State,
+    depth: u32,
+    eval: &E,
+    cfg: AlphaBetaConfig,
+) -> AlphaBetaResult {
+    let mut ab = AlphaBeta::new(eval, cfg);
+    // no deadline: never interrupted
+    let (score, best_move) = ab.root(state, depth).unwrap();
+    AlphaBetaResult {
+        best_move,
+        score,
+        nodes: ab.nodes,
+    }
+}
+
+/// Iterative deepening within `max_time`: returns the best move and score
+/// from the deepest fully completed depth, plus that depth. Depth 1 always
+/// completes, so a legal move is returned whenever one exists.
+pub fn search_timed<E: Evaluator + ?Sized>(
+    state: &State,
+    max_time: Duration,
+    eval: &E,
+) -> (Option<usize>, f32, u32) {
+    let mut ab = AlphaBeta::new(eval, AlphaBetaConfig::default());
+    let (mut score, mut best) = ab.root(state, 1).unwrap();
+    let mut depth = 1;
+    if !ab.hit_horizon {
+        return (best, score, depth);
+    }
+
+    ab.deadline = Some(Instant::now() + max_time);
+    loop {
+        ab.hit_horizon = false;
+        match ab.root(state, depth + 1) {
+            Some((v, m)) => {
+                best = m;
+                score = v;
+                depth += 1;
+            }
+            None => break,
+        }
+        // the whole game tree fit inside the horizon; deeper is identical
+        if !ab.hit_horizon {
+            break;
+        }
+    }
+    (best, score, depth)
+}
+
+const KILLER_SLOTS: usize = 2;
+
+struct AlphaBeta<'a, E: ?Sized> {
+    eval: &'a E,
+    cfg: AlphaBetaConfig,
+    deadline: Option<Instant>,
+    /// Some leaf was cut off by the depth limit rather than the game ending.
+    hit_horizon: bool,
+    nodes: u64,
+    /// Best move found per position (keyed by zobrist hash), for ordering.
+    tt: HashMap<u64, usize>,
+    /// Per ply from the root: recent moves that caused a beta cutoff.
+    killers: Vec<[Option<usize>; KILLER_SLOTS]>,
+    /// Cutoff credit per side and pit.
+    history: [[u32; PITS_PER_SIDE]; 2],
+}
+
+impl<'a, E: Evaluator + ?Sized> AlphaBeta<'a, E> {
+    fn new(eval: &'a E, cfg: AlphaBetaConfig) -> Self {
+        Self {
+            eval,
+            cfg,
+            deadline: None,
+            hit_horizon: false,
+            nodes: 0,
+            tt: HashMap::new(),
+            killers: Vec::new(),
+            history: [[0; PITS_PER_SIDE]; 2],
+        }
+    }
+
+    /// `(score, best move)`, or `None` if the deadline passed first.
+    fn root(&mut self, state: &State, depth: u32) -> Option<(f32, Option<usize>)> {
+        self.negamax(state, depth, 0, f32::NEG_INFINITY, f32::INFINITY)
+    }
+
+    fn negamax(
+        &mut self,
+        state: &State,
+        depth: u32,
+        ply: usize,
+        alpha: f32,
+        beta: f32,
+    ) -> Option<(f32, Option<usize>)> {
+        let (score, best_move) = self.search_node(state, depth, ply, alpha, beta)?;
+        let score = if self.cfg.fail_soft {
+            score
+        } else {
+            score.clamp(alpha, beta)
+        };
+        Some((score, best_move))
+    }
+
+    fn search_node(
+        &mut self,
+        state: &State,
+        depth: u32,
+        ply: usize,
+        mut alpha: f32,
+        beta: f32,
+    ) -> Option<(f32, Option<usize>)> {
+        self.nodes += 1;
+        if state.is_terminal() || state.is_stalemate() {
+            return Some((terminal_value(state), None));
+        }
+        if depth == 0 {
+            self.hit_horizon = true;
+            return Some((self.eval.value(state), None));
+        }
+        if ply > 0 && self.deadline.is_some_and(|d| Instant::now() >= d) {
+            return None;
+        }
+
+        let me = state.current_player();
+        let mut moves = state.legal_transitions();
+        if self.cfg.move_ordering {
+            self.order(state, ply, &mut moves);
+        }
+
+        let mut best = f32::NEG_INFINITY;
+        let mut best_move = None;
+        for (m, child) in moves {
+            let v = if child.current_player() == me {
+                self.negamax(&child, depth - 1, ply + 1, alpha, beta)?.0
+            } else {
+                -self.negamax(&child, depth - 1, ply + 1, -beta, -alpha)?.0
+            };
+            if v > best {
+                best = v;
+                best_move = Some(m);
+            }
+            alpha = alpha.max(v);
+            if alpha >= beta {
+                if self.cfg.move_ordering {
+                    self.record_cutoff(side_index(me), ply, m, depth);
+                }
+                break;
+            }
+        }
+        if self.cfg.move_ordering
+            && let Some(m) = best_move
+        {
+            self.tt.insert(state.zobrist_hash(), m);
+        }
+        Some((best, best_move))
+    }
+
+    fn order(&self, state: &State, ply: usize, moves: &mut [(usize, State)]) {
+        let me = state.current_player();
+        let side = side_index(me);
+        let tt_move = self.tt.get(&state.zobrist_hash()).copied();
+        let killers = self.killers.get(ply).copied().unwrap_or_default();
+        let base = state.score_for(me);
+        moves.sort_by_cached_key(|(m, child)| {
+            let rank = if Some(*m) == tt_move {
+                0
+            } else if killers.contains(&Some(*m)) {
+                1
+            } else {
+                2
+            };
+            let gain = (child.score_for(me) - base) * 2 + (child.current_player() == me) as i32;
+            (rank, Reverse(gain), Reverse(self.history[side][*m]), *m)
+        });
+    }
+
+    fn record_cutoff(&mut self, side: usize, ply: usize, m: usize, depth: u32) {
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None; KILLER_SLOTS]);
+        }
+        let slots = &mut self.killers[ply];
+        if slots[0] != Some(m) {
+            slots[1] = slots[0];
+            slots[0] = Some(m);
+        }
+        self.history[side][m] += depth * depth;
+    }
+}
+
+#[inline]
+fn side_index(p: Player) -> usize {
+    match p {
+        Player::A => 0,
+        Player::B => 1,
+    }
+}
+
+/// Exact value of a terminal position for its side to move.
+fn terminal_value(state: &State) -> f32 {
+    match state.outcome() {
+        Outcome::Win(p) if p == state.current_player() => 1.0,
+        Outcome::Win(_) => -1.0,
+        Outcome::Draw | Outcome::Ongoing => 0.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mancala::{GrandSlam, Rules, StateBuilder};
+
+    /// Deterministic: store difference for the side to move.
+    struct StoreDiff;
+
+    impl Evaluator for StoreDiff {
+        fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+            let v = state.score_for(state.current_player()) as f32 / 48.0;
+            (Vec::new(), v)
+        }
+    }
+
+    /// [`StoreDiff`] that only answers value queries.
+    struct ValueOnly;
+
+    impl Evaluator for ValueOnly {
+        fn policy_value(&self, _state: &State) -> (Vec<(usize, f32)>, f32) {
+            unreachable!("the horizon only needs a value")
+        }
+
+        fn value(&self, state: &State) -> f32 {
+            StoreDiff.policy_value(state).1
+        }
+    }
+
+    #[test]
+    fn stalemate_is_scored_as_finished() {
+        // A's only move would take all of B's stones, which is forbidden
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 4, 3)
+            .store(Player::A, 23)
+            .store(Player::B, 21)
+            .rules(Rules {
+                grand_slam: GrandSlam::Forbidden,
+                ..Rules::default()
+            })
+            .build()
+            .unwrap();
+        assert!(s.is_stalemate());
+        assert_eq!(search(&s, 3, &StoreDiff), (None, 1.0));
+    }
+
+    #[test]
+    fn horizon_asks_only_for_values() {
+        let s = State::new();
+        assert_eq!(search(&s, 3, &ValueOnly), search(&s, 3, &StoreDiff));
+    }
+
+    #[test]
+    fn larger_budget_reaches_greater_depth() {
+        let s = State::new();
+        let (m1, _, d1) = search_timed(&s, Duration::ZERO, &StoreDiff);
+        let (m2, _, d2) = search_timed(&s, Duration::from_millis(200), &StoreDiff);
+        assert_eq!(d1, 1);
+        assert!(d2 > d1, "depth {d2} not greater than {d1}");
+        assert!(s.legal_moves().contains(&m1.unwrap()));
+        assert!(s.legal_moves().contains(&m2.unwrap()));
+    }
+
+    #[test]
+    fn timed_matches_fixed_depth_result() {
+        let s = State::new();
+        let (_, _, d) = search_timed(&s, Duration::from_millis(50), &StoreDiff);
+        let (m, v) = search(&s, d, &StoreDiff);
+        let (mt, vt, _) = search_timed(&s, Duration::ZERO, &StoreDiff);
+        let (m1, v1) = search(&s, 1, &StoreDiff);
+        assert_eq!((mt, vt), (m1, v1));
+        assert!(m.is_some());
+        assert!((-1.0..=1.0).contains(&v));
+    }
+
+    #[test]
+    fn finds_winning_capture() {
+        // A to move: pit 0 lands in empty pit 1 and captures B's 5 opposite.
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::A, 3, 1)
+            .pit(Player::B, PITS_PER_SIDE - 1 - 1, 5)
+            .pit(Player::B, 0, 1)
+            .build()
+            .unwrap();
+        let (m, v) = search(&s, 4, &StoreDiff);
+        assert_eq!(m, Some(0));
+        assert_eq!(v, 1.0);
+    }
+
+    #[test]
+    fn solved_tree_stops_deepening() {
+        let s = StateBuilder::new()
+            .pit(Player::A, PITS_PER_SIDE - 1, 1)
+            .pit(Player::B, PITS_PER_SIDE - 1, 1)
+            .build()
+            .unwrap();
+        let (m, _, d) = search_timed(&s, Duration::from_secs(5), &StoreDiff);
+        assert_eq!(m, Some(PITS_PER_SIDE - 1));
+        assert!(d <= 2);
+    }
+
+    #[test]
+    fn terminal_root_has_no_move() {
+        let s = StateBuilder::new().store(Player::A, 2).build().unwrap();
+        let (m, v) = search(&s, 3, &StoreDiff);
+        assert_eq!(m, None);
+        assert_eq!(v, 1.0);
+    }
+
+    #[test]
+    fn ordering_visits_fewer_nodes_with_same_score() {
+        let s = State::new().child_after_move(1).unwrap();
+        let plain = search_with(
+            &s,
+            7,
+            &StoreDiff,
+            AlphaBetaConfig {
+                move_ordering: false,
+                ..Default::default()
+            },
+        );
+        let ordered = search_with(&s, 7, &StoreDiff, AlphaBetaConfig::default());
+        assert_eq!(plain.score, ordered.score);
+        assert!(
+            ordered.nodes < plain.nodes,
+            "ordered {} >= plain {}",
+            ordered.nodes,
+            plain.nodes
+        );
+    }
+
+    #[test]
+    fn fail_hard_agrees_with_fail_soft_at_the_root() {
+        let s = State::new().child_after_move(3).unwrap();
+        for move_ordering in [false, true] {
+            let soft = search_with(
+                &s,
+                6,
+                &StoreDiff,
+                AlphaBetaConfig {
+                    move_ordering,
+                    fail_soft: true,
+                },
+            );
+            let hard = search_with(
+                &s,
+                6,
+                &StoreDiff,
+                AlphaBetaConfig {
+                    move_ordering,
+                    fail_soft: false,
+                },
+            );
+            assert_eq!(soft.best_move, hard.best_move);
+            assert_eq!(soft.score, hard.score);
+            assert!(soft.nodes > 0 && hard.nodes > 0);
+        }
+    }
+}
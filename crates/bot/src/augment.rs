@@ -0,0 +1,57 @@
+//! Training-data augmentation from the side-swap symmetry.
+
+use super::replay::Sample;
+
+/// Action in the mirrored position equivalent to `action` here. Pits are
+/// indexed from the mover's side, so this is the identity; kept as the
+/// single place policy targets are remapped.
+#[inline]
+pub fn mirror_action(action: usize) -> usize {
+    action
+}
+
+/// `sample` with the sides swapped. The value target is relative to the
+/// side to move and carries over unchanged.
+pub fn mirror_sample(sample: &Sample) -> Sample {
+    Sample {
+        state: sample.state.mirror(),
+        policy: sample
+            .policy
+            .iter()
+            .map(|&(a, p)| (mirror_action(a), p))
+            .collect(),
+        value: sample.value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mancala::State;
+
+    #[test]
+    fn mirroring_twice_is_identity_and_policy_follows_moves() {
+        let state = State::new().child_after_move(0).unwrap();
+        let sample = Sample {
+            state: state.clone(),
+            policy: vec![(1, 0.5), (3, 0.3), (5, 0.2)],
+            value: -0.25,
+        };
+        let m = mirror_sample(&sample);
+        assert_eq!(m.value, sample.value);
+        assert_eq!(m.policy.len(), sample.policy.len());
+        for (&(a, p), &(ma, mp)) in sample.policy.iter().zip(&m.policy) {
+            assert_eq!(p, mp);
+            // the remapped action plays the mirrored move
+            assert!(
+                m.state.child_after_move(ma).unwrap()
+                    == state.child_after_move(a).unwrap().mirror()
+            );
+        }
+
+        let back = mirror_sample(&m);
+        assert!(back.state == sample.state);
+        assert_eq!(back.policy, sample.policy);
+        assert_eq!(back.value, sample.value);
+    }
+}
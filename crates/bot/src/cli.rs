@@ -0,0 +1,86 @@
+//! One-shot queries for scripting: position string in, move and new position
+//! out.
+
+use std::fmt::{self, Display, Formatter};
+
+use mancala::{FenError, State};
+
+use super::evaluator::Evaluator;
+use super::mcts::{SearchConfig, mcts_search};
+
+/// Why a query produced no move.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    /// The position string could not be parsed.
+    Fen(FenError),
+    /// The position is terminal.
+    GameOver,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Fen(e) => write!(f, "{e}"),
+            Error::GameOver => write!(f, "game is over"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<FenError> for Error {
+    fn from(e: FenError) -> Self {
+        Error::Fen(e)
+    }
+}
+
+/// Search the position in `fen` and return the chosen pit together with the
+/// successor position's notation.
+pub fn best_move_from_fen(
+    fen: &str,
+    cfg: SearchConfig,
+    eval: &dyn Evaluator,
+) -> Result<(usize, String), Error> {
+    let state = State::from_fen(fen)?;
+    let action = mcts_search(&state, cfg, &eval)
+        .chosen_action
+        .ok_or(Error::GameOver)?;
+    let next = state
+        .child_after_move(action)
+        .expect("search returns legal moves");
+    Ok((action, next.to_fen()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+
+    fn cfg() -> SearchConfig {
+        SearchConfig {
+            simulations: 200,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn opening_yields_legal_move_and_successor() {
+        let start = State::new();
+        let eval = RandomEvaluator::new(32);
+        let (m, fen) = best_move_from_fen(&start.to_fen(), cfg(), &eval).unwrap();
+        assert!(start.legal_moves().contains(&m));
+        let next = State::from_fen(&fen).unwrap();
+        assert_eq!(next.is_successor_of(&start), Some(m));
+    }
+
+    #[test]
+    fn reports_parse_errors_and_finished_games() {
+        let eval = RandomEvaluator::new(32);
+        assert_eq!(
+            best_move_from_fen("nonsense", cfg(), &eval),
+            Err(Error::Fen(FenError::Malformed))
+        );
+        let over = "0,0,0,0,0,0/0,0,0,0,0,0 30 18 B";
+        assert_eq!(best_move_from_fen(over, cfg(), &eval), Err(Error::GameOver));
+    }
+}
@@ -0,0 +1,67 @@
+//! Game loops behind the examples, writing to any sink so they can be
+//! tested.
+
+use std::io::{self, Write};
+
+use mancala::{Outcome, State};
+
+use super::evaluator::Evaluator;
+use super::mcts::{SearchConfig, mcts_search};
+
+/// Play one game of `eval`-guided MCTS against itself from the opening,
+/// printing each move and board to `out`, then a final `Result:` line.
+pub fn run_bot_vs_bot<E: Evaluator>(
+    out: &mut impl Write,
+    cfg: SearchConfig,
+    eval: &E,
+) -> io::Result<Outcome> {
+    let mut s = State::new();
+    eval.prepare();
+
+    writeln!(out, "== Bot vs Bot ==")?;
+    writeln!(out, "{s}")?;
+
+    while !s.is_terminal() {
+        let to_move = s.current_player();
+        let report = mcts_search(&s, cfg, eval);
+        let (Some(action), Some(next)) = (report.chosen_action, report.chosen_state) else {
+            writeln!(out, "No legal moves. Stalemate?")?;
+            break;
+        };
+        writeln!(out, ">> {to_move} plays pit index {action}")?;
+        s = next;
+        writeln!(out, "{s}")?;
+    }
+
+    let (a, b) = s.stores();
+    let outcome = s.outcome();
+    match outcome {
+        Outcome::Win(p) => writeln!(out, "Result: {p} wins.  score A={a}, B={b}")?,
+        Outcome::Draw => writeln!(out, "Result: Draw.      score A={a}, B={b}")?,
+        Outcome::Ongoing => writeln!(out, "Result: Ongoing? (should not happen)")?,
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+
+    #[test]
+    fn bot_vs_bot_ends_with_a_result_line() {
+        let cfg = SearchConfig {
+            simulations: 30,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        let outcome = run_bot_vs_bot(&mut out, cfg, &RandomEvaluator::new(16)).unwrap();
+        assert_ne!(outcome, Outcome::Ongoing);
+
+        let text = String::from_utf8(out).unwrap();
+        let last = text.lines().last().unwrap();
+        assert!(last.starts_with("Result: "), "{last}");
+        assert!(last.contains("score A="));
+        assert!(text.contains(">> "));
+    }
+}
@@ -0,0 +1,166 @@
+//! Offline snapshots of a search tree.
+//!
+//! A [`TreeDump`] is a plain copy of every node, detached from the search,
+//! that can be written to JSON and read back for inspection. Positions are
+//! stored as their [`State::to_bytes`] encoding, so rules survive.
+
+use std::fmt::{self, Display, Formatter};
+
+use mancala::State;
+
+use super::json::{self, ParseError, Value};
+use super::node::Node;
+
+/// Why a dump could not be read.
+#[derive(Debug)]
+pub enum DumpError {
+    Parse(ParseError),
+    /// The JSON parsed but is not a tree dump.
+    Shape(&'static str),
+}
+
+impl Display for DumpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpError::Parse(e) => write!(f, "{e}"),
+            DumpError::Shape(what) => write!(f, "bad tree dump: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+/// One node of a dumped tree and, recursively, everything below it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeDump {
+    pub state: State,
+    /// Pit played from the parent; `None` at the root.
+    pub action: Option<usize>,
+    pub prior: f32,
+    pub visits: u32,
+    /// Sum of backed-up values from `state`'s side to move.
+    pub value_sum: f32,
+    /// `(action, prior)` of moves not yet expanded.
+    pub unexpanded: Vec<(usize, f32)>,
+    pub children: Vec<TreeDump>,
+}
+
+impl TreeDump {
+    pub(crate) fn from_node(node: &Node, action: Option<usize>) -> Self {
+        Self {
+            state: node.state.clone(),
+            action,
+            prior: node.prior,
+            visits: node.visits,
+            value_sum: node.value_sum,
+            unexpanded: node.unexpanded.clone(),
+            children: node
+                .children
+                .iter()
+                .map(|c| Self::from_node(c, c.state.last_move()))
+                .collect(),
+        }
+    }
+
+    /// Mean backed-up value, `0.0` if never visited.
+    pub fn value_mean(&self) -> f32 {
+        if self.visits == 0 {
+            0.0
+        } else {
+            self.value_sum / self.visits as f32
+        }
+    }
+
+    /// The child reached by playing `action`.
+    pub fn child(&self, action: usize) -> Option<&TreeDump> {
+        self.children.iter().find(|c| c.action == Some(action))
+    }
+
+    /// Nodes in this subtree, itself included.
+    pub fn node_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(TreeDump::node_count)
+            .sum::<usize>()
+    }
+
+    pub fn to_json(&self) -> String {
+        self.to_value().to_string()
+    }
+
+    /// Inverse of [`to_json`](Self::to_json).
+    pub fn from_json(text: &str) -> Result<Self, DumpError> {
+        let v = json::parse(text).map_err(DumpError::Parse)?;
+        Self::from_value(&v)
+    }
+
+    fn to_value(&self) -> Value {
+        let bytes = self
+            .state
+            .to_bytes()
+            .iter()
+            .map(|&b| (b as u32).into())
+            .collect();
+        let unexpanded = self
+            .unexpanded
+            .iter()
+            .map(|&(a, p)| Value::Array(vec![a.into(), p.into()]))
+            .collect();
+        Value::Object(vec![
+            ("state".into(), Value::Array(bytes)),
+            ("action".into(), self.action.into()),
+            ("prior".into(), self.prior.into()),
+            ("visits".into(), self.visits.into()),
+            ("value_sum".into(), self.value_sum.into()),
+            ("unexpanded".into(), Value::Array(unexpanded)),
+            (
+                "children".into(),
+                Value::Array(self.children.iter().map(TreeDump::to_value).collect()),
+            ),
+        ])
+    }
+
+    fn from_value(v: &Value) -> Result<Self, DumpError> {
+        let field = |key| v.get(key).ok_or(DumpError::Shape("missing field"));
+        let array = |key| {
+            field(key)?
+                .as_array()
+                .ok_or(DumpError::Shape("expected an array"))
+        };
+
+        let bytes = array("state")?
+            .iter()
+            .map(|b| number(b).map(|n| n as u8))
+            .collect::<Result<Vec<_>, _>>()?;
+        let state = State::from_bytes(&bytes).map_err(|_| DumpError::Shape("bad state"))?;
+        let action = match field("action")? {
+            Value::Null => None,
+            a => Some(number(a)? as usize),
+        };
+        let unexpanded = array("unexpanded")?
+            .iter()
+            .map(|pair| match pair.as_array() {
+                Some([a, p]) => Ok((number(a)? as usize, number(p)? as f32)),
+                _ => Err(DumpError::Shape("expected an [action, prior] pair")),
+            })
+            .collect::<Result<_, _>>()?;
+        let children = array("children")?
+            .iter()
+            .map(TreeDump::from_value)
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            state,
+            action,
+            prior: number(field("prior")?)? as f32,
+            visits: number(field("visits")?)? as u32,
+            value_sum: number(field("value_sum")?)? as f32,
+            unexpanded,
+            children,
+        })
+    }
+}
+
+fn number(v: &Value) -> Result<f64, DumpError> {
+    v.as_f64().ok_or(DumpError::Shape("expected a number"))
+}
@@ -1,20 +1,125 @@
-use rand::seq::IndexedRandom;
+use std::sync::Arc;
 
-use mancala::{Outcome, State};
+use mancala::{Outcome, State, random_playout_len};
 
 /// policy: (action_index, prior in [0,1])  /  value in [-1,1] for current player.
+///
+/// Calls take `&self`, so an evaluator shared between threads (see
+/// [`SharedEvaluator`]) keeps any mutable state behind interior mutability:
+/// a `Mutex`, atomics, or per-thread values such as `rand::rng()`.
 pub trait Evaluator {
     fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32);
+
+    /// Value only. Override when it is cheaper than the full call.
+    fn value(&self, state: &State) -> f32 {
+        self.policy_value(state).1
+    }
+
+    /// Policy only. Override when it is cheaper than the full call.
+    fn policy(&self, state: &State) -> Vec<(usize, f32)> {
+        self.policy_value(state).0
+    }
+
+    /// One-time setup before a search or a game, such as loading weights
+    /// or allocating buffers. Called once per [`mcts_search`] (before any
+    /// evaluation) and once per game by the game loops.
+    ///
+    /// [`mcts_search`]: crate::mcts_search
+    fn prepare(&self) {}
+}
+
+impl<E: Evaluator + ?Sized> Evaluator for &E {
+    fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        (**self).policy_value(state)
+    }
+
+    fn value(&self, state: &State) -> f32 {
+        (**self).value(state)
+    }
+
+    fn policy(&self, state: &State) -> Vec<(usize, f32)> {
+        (**self).policy(state)
+    }
+
+    fn prepare(&self) {
+        (**self).prepare()
+    }
+}
+
+impl<E: Evaluator + ?Sized> Evaluator for Arc<E> {
+    fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        (**self).policy_value(state)
+    }
+
+    fn value(&self, state: &State) -> f32 {
+        (**self).value(state)
+    }
+
+    fn policy(&self, state: &State) -> Vec<(usize, f32)> {
+        (**self).policy(state)
+    }
+
+    fn prepare(&self) {
+        (**self).prepare()
+    }
+}
+
+/// An evaluator that can be handed to several search threads.
+pub type SharedEvaluator = Arc<dyn Evaluator + Send + Sync>;
+
+/// Prior multipliers for tactical features of a move, applied before the
+/// policy is renormalized. The default (all `1.0`) keeps it uniform.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct PolicyShaping {
+    /// Moves that capture.
+    pub capture: f32,
+    /// Moves that grant another turn.
+    pub extra_turn: f32,
+    /// Moves after which the opponent can capture (see
+    /// [`State::safe_moves`]).
+    pub exposed: f32,
+}
+
+impl Default for PolicyShaping {
+    fn default() -> Self {
+        Self {
+            capture: 1.0,
+            extra_turn: 1.0,
+            exposed: 1.0,
+        }
+    }
 }
 
 /// Baseline: uniform policy + light random rollout for value.
+///
+/// Rollouts draw from the calling thread's `rand::rng()`, so it is
+/// `Send + Sync` and can be shared as a [`SharedEvaluator`].
 pub struct RandomEvaluator {
     playout_max_len: usize,
+    shaping: PolicyShaping,
+    decay: f32,
 }
 
 impl RandomEvaluator {
     pub fn new(playout_max_len: usize) -> Self {
-        Self { playout_max_len }
+        Self {
+            playout_max_len,
+            shaping: PolicyShaping::default(),
+            decay: 1.0,
+        }
+    }
+
+    /// Scale the rollout outcome by `decay` per ply played, so long
+    /// playouts count for less. `1.0` (the default) keeps the raw outcome.
+    pub fn with_decay(mut self, decay: f32) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Weight the uniform prior by `shaping`.
+    pub fn with_shaping(mut self, shaping: PolicyShaping) -> Self {
+        self.shaping = shaping;
+        self
     }
 }
 
@@ -24,44 +129,330 @@ impl Default for RandomEvaluator {
     }
 }
 
+impl RandomEvaluator {
+    fn shaped_policy(&self, state: &State, legal: Vec<usize>) -> Vec<(usize, f32)> {
+        let sh = self.shaping;
+        let captures = state.capturing_moves();
+        let extra = state.extra_turn_moves();
+        let safe = state.safe_moves();
+        let weights: Vec<f32> = legal
+            .iter()
+            .map(|m| {
+                let mut w = 1.0;
+                if captures.contains(m) {
+                    w *= sh.capture;
+                }
+                if extra.contains(m) {
+                    w *= sh.extra_turn;
+                }
+                if !safe.contains(m) {
+                    w *= sh.exposed;
+                }
+                w.max(0.0)
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        legal
+            .iter()
+            .zip(&weights)
+            .map(|(&a, &w)| {
+                let p = if total > 0.0 {
+                    w / total
+                } else {
+                    1.0 / legal.len() as f32
+                };
+                (a, p)
+            })
+            .collect()
+    }
+}
+
 impl Evaluator for RandomEvaluator {
     fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        (self.policy(state), self.value(state))
+    }
+
+    fn policy(&self, state: &State) -> Vec<(usize, f32)> {
         let legal = state.legal_moves();
-        let prior = if legal.is_empty() {
+        if legal.is_empty() {
             Vec::new()
-        } else {
+        } else if self.shaping == PolicyShaping::default() {
             let p = 1.0f32 / (legal.len() as f32);
             legal.into_iter().map(|a| (a, p)).collect()
-        };
+        } else {
+            self.shaped_policy(state, legal)
+        }
+    }
 
+    fn value(&self, state: &State) -> f32 {
         // quick rollout
-        let mut rng = rand::rng();
-        let mut s = state.clone();
-        let root_player = s.current_player();
+        let root_player = state.current_player();
+        let (outcome, plies) = random_playout_len(state, &mut rand::rng(), self.playout_max_len);
+        let v = match outcome {
+            Outcome::Win(p) if p == root_player => 1.0,
+            Outcome::Win(_) => -1.0,
+            Outcome::Draw | Outcome::Ongoing => 0.0,
+        };
+        v * self.decay.powi(plies as i32)
+    }
+}
 
-        for _ in 0..self.playout_max_len {
-            if s.is_terminal() {
-                break;
-            }
-            let moves = s.legal_moves();
-            if moves.is_empty() {
-                break;
+/// Weighted ensemble: values are averaged by weight, policies summed by
+/// weight over legal moves and renormalized.
+pub struct BlendEvaluator {
+    pub parts: Vec<(Box<dyn Evaluator>, f32)>,
+}
+
+impl BlendEvaluator {
+    pub fn new(parts: Vec<(Box<dyn Evaluator>, f32)>) -> Self {
+        Self { parts }
+    }
+}
+
+impl Evaluator for BlendEvaluator {
+    fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        let legal = state.legal_moves();
+        let mut mass = vec![0.0f32; legal.len()];
+        let mut value = 0.0;
+        let mut weight = 0.0;
+
+        for (eval, w) in &self.parts {
+            let (policy, v) = eval.policy_value(state);
+            value += w * v;
+            weight += w;
+            for (a, p) in policy {
+                if let Some(i) = legal.iter().position(|&m| m == a) {
+                    mass[i] += w * p;
+                }
             }
-            let &m = moves.choose(&mut rng).unwrap();
-            s = s.child_after_move(m).unwrap();
         }
 
-        let v = if s.is_terminal() {
-            match s.outcome() {
-                Outcome::Win(p) if p == root_player => 1.0,
-                Outcome::Win(_) => -1.0,
-                Outcome::Draw => 0.0,
-                Outcome::Ongoing => 0.0,
-            }
+        let total: f32 = mass.iter().sum();
+        let policy = legal
+            .iter()
+            .zip(&mass)
+            .map(|(&a, &m)| {
+                let p = if total > 0.0 {
+                    m / total
+                } else {
+                    1.0 / legal.len() as f32
+                };
+                (a, p)
+            })
+            .collect();
+        let value = if weight > 0.0 { value / weight } else { 0.0 };
+        (policy, value)
+    }
+
+    fn prepare(&self) {
+        for (eval, _) in &self.parts {
+            eval.prepare();
+        }
+    }
+}
+
+/// Wraps an evaluator and reshapes its policy: each legal move's prior is
+/// raised to `1 / temperature` and the result renormalized. Below `1.0`
+/// sharpens, above flattens, `1.0` leaves the policy as is. Values pass
+/// through unchanged. `temperature` must be positive.
+pub struct TemperedEvaluator<E> {
+    pub inner: E,
+    pub temperature: f32,
+}
+
+impl<E: Evaluator> TemperedEvaluator<E> {
+    pub fn new(inner: E, temperature: f32) -> Self {
+        debug_assert!(temperature > 0.0);
+        Self { inner, temperature }
+    }
+
+    fn temper(&self, state: &State, policy: Vec<(usize, f32)>) -> Vec<(usize, f32)> {
+        if self.temperature == 1.0 {
+            return policy;
+        }
+        let legal = state.legal_moves();
+        let inv = 1.0 / self.temperature;
+        let tempered: Vec<(usize, f32)> = policy
+            .into_iter()
+            .filter(|(a, _)| legal.contains(a))
+            .map(|(a, p)| (a, p.max(0.0).powf(inv)))
+            .collect();
+        let total: f32 = tempered.iter().map(|&(_, p)| p).sum();
+        if total > 0.0 {
+            tempered.into_iter().map(|(a, p)| (a, p / total)).collect()
         } else {
-            0.0
+            tempered
+        }
+    }
+}
+
+impl<E: Evaluator> Evaluator for TemperedEvaluator<E> {
+    fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        let (policy, value) = self.inner.policy_value(state);
+        (self.temper(state, policy), value)
+    }
+
+    fn value(&self, state: &State) -> f32 {
+        self.inner.value(state)
+    }
+
+    fn policy(&self, state: &State) -> Vec<(usize, f32)> {
+        self.temper(state, self.inner.policy(state))
+    }
+
+    fn prepare(&self) {
+        self.inner.prepare()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mancala::{Player, StateBuilder};
+    use std::cell::Cell;
+
+    /// Fixed value; all prior on one pit.
+    struct Fixed {
+        pit: usize,
+        value: f32,
+    }
+
+    impl Evaluator for Fixed {
+        fn policy_value(&self, _state: &State) -> (Vec<(usize, f32)>, f32) {
+            (vec![(self.pit, 1.0)], self.value)
+        }
+    }
+
+    #[test]
+    fn decay_favors_quick_wins() {
+        let build = |a, b| {
+            StateBuilder::new()
+                .pits(Player::A, a)
+                .pits(Player::B, b)
+                .store(Player::A, 30)
+                .store(Player::B, 16)
+                .build()
+                .unwrap()
         };
+        // banks the last stone at once
+        let quick = build([0, 0, 0, 0, 0, 1], [1, 0, 0, 0, 0, 0]);
+        // A moves 4 -> 5, then B's only move ends the game
+        let slow = build([0, 0, 0, 0, 1, 0], [0, 0, 0, 0, 0, 1]);
+        let eval = RandomEvaluator::new(64).with_decay(0.9);
+        let (q, s) = (eval.value(&quick), eval.value(&slow));
+        assert!((q - 0.9).abs() < 1e-6, "{q}");
+        assert!((s - 0.81).abs() < 1e-6, "{s}");
+        assert!(q.abs() > s.abs());
+        assert_eq!(RandomEvaluator::new(64).value(&slow), 1.0);
+    }
+
+    #[test]
+    fn blend_averages_values_and_normalizes_policy() {
+        let blend = BlendEvaluator::new(vec![
+            (
+                Box::new(Fixed {
+                    pit: 0,
+                    value: -0.5,
+                }),
+                1.0,
+            ),
+            (Box::new(Fixed { pit: 3, value: 1.0 }), 3.0),
+        ]);
+        let s = State::new();
+        let (policy, v) = blend.policy_value(&s);
+
+        assert!((-0.5..=1.0).contains(&v));
+        assert!((v - 0.625).abs() < 1e-6);
+
+        let actions: Vec<usize> = policy.iter().map(|&(a, _)| a).collect();
+        assert_eq!(actions, s.legal_moves());
+        let total: f32 = policy.iter().map(|&(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!((policy[0].1 - 0.25).abs() < 1e-6);
+        assert!((policy[3].1 - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn shaping_favors_captures() {
+        // pit 0 captures B's pit 4; pit 2 is quiet and safe
+        let s = StateBuilder::new()
+            .pits(Player::A, [1, 0, 3, 0, 0, 0])
+            .pits(Player::B, [0, 0, 0, 0, 4, 0])
+            .store(Player::A, 10)
+            .store(Player::B, 30)
+            .build()
+            .unwrap();
+        assert_eq!(s.capturing_moves(), [0]);
+        assert_eq!(s.safe_moves(), [0, 2]);
+
+        let uniform = RandomEvaluator::new(0).policy(&s);
+        assert_eq!(uniform, [(0, 0.5), (2, 0.5)]);
+
+        let shaping = PolicyShaping {
+            capture: 3.0,
+            ..Default::default()
+        };
+        let shaped = RandomEvaluator::new(0).with_shaping(shaping).policy(&s);
+        assert!(shaped[0].1 > shaped[1].1);
+        assert!((shaped[0].1 - 0.75).abs() < 1e-6);
+    }
+
+    /// Fixed uneven policy over the opening's moves.
+    struct Skewed;
+
+    impl Evaluator for Skewed {
+        fn policy_value(&self, _state: &State) -> (Vec<(usize, f32)>, f32) {
+            (vec![(0, 0.1), (1, 0.2), (2, 0.4), (3, 0.3)], 0.25)
+        }
+    }
+
+    #[test]
+    fn temperature_sharpens_and_flattens() {
+        let s = State::new();
+        let max_share = |t: f32| {
+            let e = TemperedEvaluator::new(Skewed, t);
+            let (policy, v) = e.policy_value(&s);
+            assert_eq!(v, 0.25);
+            assert!((policy.iter().map(|&(_, p)| p).sum::<f32>() - 1.0).abs() < 1e-6);
+            policy.iter().map(|&(_, p)| p).fold(0.0, f32::max)
+        };
+        assert_eq!(max_share(1.0), 0.4);
+        assert!(max_share(0.5) > 0.4);
+        assert!(max_share(2.0) < 0.4);
+        assert_eq!(
+            TemperedEvaluator::new(Skewed, 1.0).policy(&s),
+            Skewed.policy(&s)
+        );
+    }
+
+    /// Counts full `policy_value` calls; `value` is a cheap override.
+    #[derive(Default)]
+    struct Counting {
+        full_calls: Cell<usize>,
+    }
+
+    impl Evaluator for Counting {
+        fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+            self.full_calls.set(self.full_calls.get() + 1);
+            (RandomEvaluator::new(0).policy(state), 0.5)
+        }
+
+        fn value(&self, _state: &State) -> f32 {
+            0.5
+        }
+    }
+
+    #[test]
+    fn overridden_value_skips_policy_path() {
+        let eval = Counting::default();
+        let s = State::new();
+        assert_eq!(eval.value(&s), 0.5);
+        let by_ref = &eval;
+        assert_eq!(Evaluator::value(&by_ref, &s), 0.5);
+        assert_eq!(eval.full_calls.get(), 0);
 
-        (prior, v)
+        // the default `policy` still goes through `policy_value`
+        assert_eq!(eval.policy(&s).len(), s.legal_moves().len());
+        assert_eq!(eval.full_calls.get(), 1);
     }
 }
@@ -0,0 +1,111 @@
+//! Human-readable dataset export.
+//!
+//! JSON Lines: one object per game, with the start position in FEN, the
+//! moves, the winner (`"A"`, `"B"`, `"draw"`, or `null` for an unfinished
+//! game) and the final stores.
+
+use std::io::{self, ErrorKind, Write};
+
+use mancala::{Outcome, Player};
+
+use super::json::Value;
+use super::record::GameRecord;
+
+/// Write `games` to `out`, one line each. Fails with `InvalidData` on a
+/// record whose moves are not legal from its start.
+pub fn write_jsonl(games: &[GameRecord], out: &mut impl Write) -> io::Result<()> {
+    for game in games {
+        let end = game.final_state().ok_or_else(|| {
+            io::Error::new(ErrorKind::InvalidData, "game record has an illegal move")
+        })?;
+        let outcome = match end.outcome() {
+            Outcome::Win(Player::A) => Value::String("A".into()),
+            Outcome::Win(Player::B) => Value::String("B".into()),
+            Outcome::Draw => Value::String("draw".into()),
+            Outcome::Ongoing => Value::Null,
+        };
+        let (a, b) = end.stores();
+        let line = Value::Object(vec![
+            ("start".into(), Value::String(game.start.to_fen())),
+            (
+                "moves".into(),
+                Value::Array(game.moves.iter().map(|&m| m.into()).collect()),
+            ),
+            ("outcome".into(), outcome),
+            (
+                "stores".into(),
+                Value::Array(vec![(a as u32).into(), (b as u32).into()]),
+            ),
+        ]);
+        writeln!(out, "{line}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+    use mancala::State;
+
+    #[test]
+    fn one_line_per_game() {
+        let mut s = State::new();
+        let mut moves = Vec::new();
+        while let Some(&m) = s.legal_moves().last() {
+            moves.push(m);
+            s = s.child_after_move(m).unwrap();
+        }
+        let games = [
+            GameRecord {
+                start: State::new(),
+                moves: moves.clone(),
+            },
+            GameRecord {
+                start: State::new(),
+                moves: vec![2, 0],
+            },
+        ];
+        let mut out = Vec::new();
+        write_jsonl(&games, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first = json::parse(lines[0]).unwrap();
+        assert_eq!(
+            first.get("start").unwrap().as_str(),
+            Some(State::new().to_fen().as_str())
+        );
+        let parsed: Vec<_> = first
+            .get("moves")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_f64().unwrap() as usize)
+            .collect();
+        assert_eq!(parsed, moves);
+        let (a, b) = s.stores();
+        let winner = if a > b {
+            "A"
+        } else if b > a {
+            "B"
+        } else {
+            "draw"
+        };
+        assert_eq!(first.get("outcome").unwrap().as_str(), Some(winner));
+        let stores = first.get("stores").unwrap().as_array().unwrap();
+        assert_eq!(stores[0].as_f64(), Some(a as f64));
+
+        let second = json::parse(lines[1]).unwrap();
+        assert_eq!(second.get("outcome"), Some(&Value::Null));
+
+        let bad = [GameRecord {
+            start: State::new(),
+            moves: vec![9],
+        }];
+        let err = write_jsonl(&bad, &mut Vec::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+}
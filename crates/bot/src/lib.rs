@@ -1,7 +1,38 @@
+pub mod alphabeta;
+pub mod augment;
+pub mod cli;
+pub mod demo;
+pub mod dump;
 pub mod evaluator;
+pub mod export;
+pub mod json;
+pub mod linear;
 pub mod mcts;
 pub mod node;
+pub mod parallel;
+pub mod rating;
+pub mod record;
+pub mod replay;
+pub mod score_cache;
+pub mod searcher;
+pub mod selfplay;
+pub mod temperature;
+pub mod tournament;
+pub mod transposition;
 
-pub use evaluator::{Evaluator, RandomEvaluator};
-pub use mcts::{mcts_search, SearchConfig, SearchReport};
+pub use dump::TreeDump;
+pub use evaluator::{
+    BlendEvaluator, Evaluator, PolicyShaping, RandomEvaluator, SharedEvaluator, TemperedEvaluator,
+};
+pub use linear::LinearEvaluator;
+pub use mcts::{SearchConfig, SearchReport, SelectionPolicy, mcts_search};
 pub use node::Node;
+pub use parallel::mcts_search_parallel;
+pub use record::GameRecord;
+pub use replay::{ReplayBuffer, Sample};
+pub use score_cache::ScoreCache;
+pub use searcher::{ChildStats, Searcher};
+pub use selfplay::self_play_game;
+pub use temperature::TemperatureSchedule;
+pub use tournament::{PairRecord, TournamentResult};
+pub use transposition::TranspositionTable;
@@ -0,0 +1,264 @@
+//! Linear evaluator with weights loaded from a JSON file.
+
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+use std::{fs, io};
+
+use mancala::{PITS_PER_SIDE, Player, State, TOTAL_CELLS};
+
+use super::evaluator::Evaluator;
+use super::json::{self, ParseError, Value};
+
+/// Length of [`feature_vector`] output.
+pub const FEATURES: usize = 2 * PITS_PER_SIDE + 4;
+
+/// Position features from the side to move's point of view, each stone
+/// count divided by the total stones on the board:
+///
+/// - `0..P`: the mover's pits, index order
+/// - `P..2P`: the opponent's pits, index order
+/// - `2P`, `2P + 1`: the mover's store, the opponent's store
+/// - `2P + 2`: phase, the fraction of stones already in stores (0 at the
+///   opening, 1 when finished)
+/// - `2P + 3`: turn, `1.0` when A is to move and `-1.0` for B
+///
+/// where `P` is `PITS_PER_SIDE`.
+pub fn feature_vector(state: &State) -> Vec<f32> {
+    let me = state.current_player();
+    let them = me.opponent();
+    let total = (state.total_stones() as f32).max(1.0);
+    let stores = state.store(me) as f32 + state.store(them) as f32;
+
+    let mut x = Vec::with_capacity(FEATURES);
+    x.extend(state.pits(me).iter().map(|&n| n as f32 / total));
+    x.extend(state.pits(them).iter().map(|&n| n as f32 / total));
+    x.push(state.store(me) as f32 / total);
+    x.push(state.store(them) as f32 / total);
+    x.push(stores / total);
+    x.push(if me == Player::A { 1.0 } else { -1.0 });
+    x
+}
+
+/// Number of planes in [`feature_planes`] output.
+pub const PLANES: usize = 4;
+
+/// The position as 1-D planes over the board's cells, for convolutional
+/// input. Cells run in sowing order from the side to move's point of view:
+/// the mover's pits, the mover's store, the opponent's pits, the
+/// opponent's store. Planes:
+///
+/// - `0`: stones per cell divided by the total stones on the board
+/// - `1`: `1.0` on the pits holding a legal move, `0.0` elsewhere
+/// - `2`: turn, `1.0` everywhere when A is to move and `-1.0` for B
+/// - `3`: phase, as in [`feature_vector`], on every cell
+pub fn feature_planes(state: &State) -> [[f32; TOTAL_CELLS]; PLANES] {
+    let me = state.current_player();
+    let them = me.opponent();
+    let total = (state.total_stones() as f32).max(1.0);
+    let stores = state.store(me) as f32 + state.store(them) as f32;
+
+    let mut planes = [[0.0; TOTAL_CELLS]; PLANES];
+    for (i, side) in [me, them].into_iter().enumerate() {
+        let base = i * (PITS_PER_SIDE + 1);
+        for (pit, &n) in state.pits(side).iter().enumerate() {
+            planes[0][base + pit] = n as f32 / total;
+        }
+        planes[0][base + PITS_PER_SIDE] = state.store(side) as f32 / total;
+    }
+    for m in state.legal_moves() {
+        planes[1][m] = 1.0;
+    }
+    planes[2] = [if me == Player::A { 1.0 } else { -1.0 }; TOTAL_CELLS];
+    planes[3] = [stores / total; TOTAL_CELLS];
+    planes
+}
+
+/// Why a weight file could not be loaded.
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Parse(ParseError),
+    /// The JSON parsed but the weights have the wrong layout.
+    Shape(&'static str),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{e}"),
+            LoadError::Parse(e) => write!(f, "{e}"),
+            LoadError::Shape(what) => write!(f, "bad weights: {what}"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// `value = tanh(w · x)`; policy is a softmax over legal moves of one linear
+/// row per pit, with `x` = [`feature_vector`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearEvaluator {
+    pub value: [f32; FEATURES],
+    pub policy: [[f32; FEATURES]; PITS_PER_SIDE],
+}
+
+impl LinearEvaluator {
+    pub fn new(value: [f32; FEATURES], policy: [[f32; FEATURES]; PITS_PER_SIDE]) -> Self {
+        Self { value, policy }
+    }
+
+    /// Load `{"value": [..FEATURES], "policy": [[..FEATURES]; PITS_PER_SIDE]}`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let text = fs::read_to_string(path).map_err(LoadError::Io)?;
+        Self::from_json(&text)
+    }
+
+    /// [`from_file`](Self::from_file) on text already in memory.
+    pub fn from_json(text: &str) -> Result<Self, LoadError> {
+        let v = json::parse(text).map_err(LoadError::Parse)?;
+        let value = row(v.get("value").ok_or(LoadError::Shape("missing value"))?)?;
+        let rows = v
+            .get("policy")
+            .and_then(Value::as_array)
+            .ok_or(LoadError::Shape("missing policy"))?;
+        if rows.len() != PITS_PER_SIDE {
+            return Err(LoadError::Shape("policy needs one row per pit"));
+        }
+        let mut policy = [[0.0; FEATURES]; PITS_PER_SIDE];
+        for (dst, src) in policy.iter_mut().zip(rows) {
+            *dst = row(src)?;
+        }
+        Ok(Self { value, policy })
+    }
+}
+
+fn row(v: &Value) -> Result<[f32; FEATURES], LoadError> {
+    let items = v.as_array().ok_or(LoadError::Shape("expected an array"))?;
+    if items.len() != FEATURES {
+        return Err(LoadError::Shape("row length must equal FEATURES"));
+    }
+    let mut out = [0.0; FEATURES];
+    for (dst, src) in out.iter_mut().zip(items) {
+        *dst = src.as_f64().ok_or(LoadError::Shape("expected a number"))? as f32;
+    }
+    Ok(out)
+}
+
+fn dot(w: &[f32; FEATURES], x: &[f32]) -> f32 {
+    w.iter().zip(x).map(|(a, b)| a * b).sum()
+}
+
+impl Evaluator for LinearEvaluator {
+    fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+        let x = feature_vector(state);
+        let legal = state.legal_moves();
+        let logits: Vec<f32> = legal.iter().map(|&a| dot(&self.policy[a], &x)).collect();
+        let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exp: Vec<f32> = logits.iter().map(|l| (l - max).exp()).collect();
+        let sum: f32 = exp.iter().sum();
+        let policy = legal
+            .into_iter()
+            .zip(exp)
+            .map(|(a, e)| (a, e / sum))
+            .collect();
+        (policy, dot(&self.value, &x).tanh())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mancala::StateBuilder;
+
+    /// Store difference only; uniform policy.
+    fn store_diff() -> LinearEvaluator {
+        let mut value = [0.0; FEATURES];
+        value[2 * PITS_PER_SIDE] = 4.0;
+        value[2 * PITS_PER_SIDE + 1] = -4.0;
+        LinearEvaluator::new(value, [[0.0; FEATURES]; PITS_PER_SIDE])
+    }
+
+    #[test]
+    fn features_are_mover_relative() {
+        let s = State::new().child_after_move(0).unwrap();
+        let x = feature_vector(&s);
+        assert_eq!(x.len(), FEATURES);
+        assert_eq!(x[2 * PITS_PER_SIDE + 3], -1.0);
+        let mirrored = feature_vector(&s.mirror());
+        assert_eq!(x[..FEATURES - 1], mirrored[..FEATURES - 1]);
+    }
+
+    #[test]
+    fn planes_mark_legal_moves() {
+        let s = State::new()
+            .child_after_move(2)
+            .unwrap()
+            .child_after_move(1)
+            .unwrap();
+        let planes = feature_planes(&s);
+        assert_eq!(planes.len(), PLANES);
+        let mask = (0..TOTAL_CELLS)
+            .filter(|&c| planes[1][c] == 1.0)
+            .fold(0u16, |m, c| m | 1 << c);
+        assert_eq!(mask, s.legal_moves_mask());
+        let x = feature_vector(&s);
+        assert_eq!(planes[0][..PITS_PER_SIDE], x[..PITS_PER_SIDE]);
+        assert_eq!(planes[0][PITS_PER_SIDE], x[2 * PITS_PER_SIDE]);
+        assert_eq!(planes[3][0], x[2 * PITS_PER_SIDE + 2]);
+        let sum: f32 = planes[0].iter().sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn hand_set_weights_favor_the_leader() {
+        let ahead = StateBuilder::new()
+            .pits(Player::A, [1; PITS_PER_SIDE])
+            .pits(Player::B, [1; PITS_PER_SIDE])
+            .store(Player::A, 20)
+            .store(Player::B, 4)
+            .build()
+            .unwrap();
+        let eval = store_diff();
+        let (policy, v) = eval.policy_value(&ahead);
+        assert!(v > 0.0);
+        let total: f32 = policy.iter().map(|&(_, p)| p).sum();
+        assert!((total - 1.0).abs() < 1e-6);
+
+        let behind = StateBuilder::new()
+            .pits(Player::A, [1; PITS_PER_SIDE])
+            .pits(Player::B, [1; PITS_PER_SIDE])
+            .store(Player::A, 20)
+            .store(Player::B, 4)
+            .to_move(Player::B)
+            .build()
+            .unwrap();
+        assert!(eval.policy_value(&behind).1 < 0.0);
+    }
+
+    #[test]
+    fn loads_from_file() {
+        let eval = store_diff();
+        let rows = |r: &[f32; FEATURES]| Value::Array(r.iter().map(|&w| w.into()).collect());
+        let doc = Value::Object(vec![
+            ("value".into(), rows(&eval.value)),
+            (
+                "policy".into(),
+                Value::Array(eval.policy.iter().map(rows).collect()),
+            ),
+        ]);
+        let path = std::env::temp_dir().join(format!("linear-{}.json", std::process::id()));
+        fs::write(&path, doc.to_string()).unwrap();
+        let loaded = LinearEvaluator::from_file(&path);
+        fs::remove_file(&path).ok();
+        assert_eq!(loaded.unwrap(), eval);
+
+        assert!(matches!(
+            LinearEvaluator::from_json("{\"value\": [1, 2]}"),
+            Err(LoadError::Shape(_))
+        ));
+        assert!(matches!(
+            LinearEvaluator::from_file(std::env::temp_dir().join("no-such-weights.json")),
+            Err(LoadError::Io(_))
+        ));
+    }
+}
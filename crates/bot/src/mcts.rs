@@ -1,12 +1,51 @@
-use mancala::{Outcome, State};
+use std::time::{Duration, Instant};
+
+use mancala::{Outcome, PITS_PER_SIDE, Player, State};
+use rand::Rng;
+use rand::seq::IndexedRandom;
 
 use super::evaluator::Evaluator;
+use super::json::Value;
 use super::node::Node;
+use super::searcher::Searcher;
+use super::transposition::TranspositionTable;
 
 #[derive(Copy, Clone)]
 pub struct SearchConfig {
     pub simulations: u32,
     pub c_puct: f32,
+    /// Value of a draw for the side to move at the root (the opponent gets
+    /// its negation). Below zero avoids draws, above zero seeks them.
+    pub draw_value: f32,
+    /// Share visit and value totals between nodes holding the same position
+    /// (see [`TranspositionTable`]), so the tree searches like a DAG.
+    pub transpositions: bool,
+    /// Visit every legal root move at least once before selection goes
+    /// deeper, including moves the evaluator's policy leaves out.
+    pub ensure_root_breadth: bool,
+    /// Factor applied to the backed-up value per ply, so nearer results
+    /// weigh more. `1.0` disables discounting.
+    pub discount: f32,
+    /// Cap on tree nodes, root included. Once reached, simulations stop
+    /// expanding and only select and back up through the existing tree.
+    pub max_nodes: Option<usize>,
+    /// How [`SearchReport::chosen_action`] is picked from the root children.
+    pub selection: SelectionPolicy,
+}
+
+/// Rule for picking the move to play from the root's visited children.
+/// Remaining ties go to the lower pit index.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SelectionPolicy {
+    /// The robust child: most visits, ties broken by mean value.
+    #[default]
+    MostVisits,
+    /// The max child: highest mean value, ties broken by visits.
+    HighestValue,
+    /// Highest mean value among the children with at least half as many
+    /// visits as the most visited one, so a barely explored move cannot win
+    /// on a lucky value.
+    VisitsThenValue,
 }
 
 impl Default for SearchConfig {
@@ -14,115 +53,662 @@ impl Default for SearchConfig {
         Self {
             simulations: 10_000,
             c_puct: 1.4,
+            draw_value: 0.0,
+            transpositions: false,
+            ensure_root_breadth: true,
+            discount: 1.0,
+            max_nodes: None,
+            selection: SelectionPolicy::MostVisits,
         }
     }
 }
 
 pub struct SearchReport {
     pub chosen_action: Option<usize>,
+    /// Position after `chosen_action`, taken from the search tree.
+    pub chosen_state: Option<State>,
     pub root_visits: u32,
+    /// Mean backed-up value at the root, for the side to move.
+    pub root_value: f32,
     pub child_visits: Vec<(usize, u32)>, // (action, visits)
+    pub child_values: Vec<(usize, f32)>, // (action, mean value for the root's side to move)
+    pub simulations: u32,
+    pub elapsed: Duration,
 }
 
-/// Run MCTS and return argmax-visit action.
-pub fn mcts_search<E: Evaluator>(root_state: &State, cfg: SearchConfig, eval: &E) -> SearchReport {
-    let (root_priors, _root_v) = eval.policy_value(root_state);
-    let mut root = Node::new_root(root_state.clone(), &root_priors);
+impl SearchReport {
+    /// A root move drawn with probability proportional to
+    /// `visits^(1 / temperature)`. At a temperature of zero or below this is
+    /// [`chosen_action`](Self::chosen_action). `None` when nothing was
+    /// visited.
+    pub fn sample_action(&self, temperature: f32, rng: &mut impl Rng) -> Option<usize> {
+        if temperature <= 0.0 {
+            return self.chosen_action;
+        }
+        let visited: Vec<(usize, u32)> = self
+            .child_visits
+            .iter()
+            .copied()
+            .filter(|&(_, n)| n > 0)
+            .collect();
+        // scale by the largest count so high powers stay finite
+        let top = visited.iter().map(|&(_, n)| n).max()? as f64;
+        let weight = |&(_, n): &(usize, u32)| (n as f64 / top).powf(1.0 / temperature as f64);
+        visited
+            .choose_weighted(rng, weight)
+            .ok()
+            .map(|&(a, _)| a)
+            .or(self.chosen_action)
+    }
+
+    /// Root visit counts normalized to sum to 1, indexed by pit. All zero
+    /// when nothing was visited.
+    pub fn policy_target(&self) -> [f32; PITS_PER_SIDE] {
+        let mut target = [0.0; PITS_PER_SIDE];
+        let total: u32 = self.child_visits.iter().map(|&(_, n)| n).sum();
+        if total == 0 {
+            return target;
+        }
+        for &(a, n) in &self.child_visits {
+            if let Some(t) = target.get_mut(a) {
+                *t = n as f32 / total as f32;
+            }
+        }
+        target
+    }
+
+    /// How unevenly [`policy_target`](Self::policy_target) treats root moves
+    /// that ought to be interchangeable, for a report on `state`.
+    ///
+    /// Mirroring keeps pit indices, so the symmetry to expect is between
+    /// moves whose successors are the same position or mirror images of
+    /// each other (equal [`State::canonical_key`]). The error is the sum,
+    /// over such groups, of each move's distance from its group's mean
+    /// share: `0` when every group is split evenly, at most `2`. Positions
+    /// without such moves, including the opening, always score `0`.
+    pub fn symmetry_error(&self, state: &State) -> f32 {
+        let target = self.policy_target();
+        let mut groups: Vec<(_, Vec<f32>)> = Vec::new();
+        for (a, next) in state.legal_transitions() {
+            let key = next.canonical_key();
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, shares)) => shares.push(target[a]),
+                None => groups.push((key, vec![target[a]])),
+            }
+        }
+        groups
+            .iter()
+            .map(|(_, shares)| {
+                let mean = shares.iter().sum::<f32>() / shares.len() as f32;
+                shares.iter().map(|p| (p - mean).abs()).sum::<f32>()
+            })
+            .sum()
+    }
+
+    /// `(1 - q_weight) * visits + q_weight * softmax(Q)` over the explored
+    /// root children, with visits as in [`policy_target`](Self::policy_target).
+    /// `q_weight = 0` is the visit target itself.
+    pub fn policy_target_blended(&self, q_weight: f32) -> [f32; PITS_PER_SIDE] {
+        let mut target = self.policy_target();
+        if q_weight == 0.0 || self.child_values.is_empty() {
+            return target;
+        }
+        let max = self
+            .child_values
+            .iter()
+            .map(|&(_, q)| q)
+            .fold(f32::NEG_INFINITY, f32::max);
+        let sum: f32 = self
+            .child_values
+            .iter()
+            .map(|&(_, q)| (q - max).exp())
+            .sum();
+        for t in &mut target {
+            *t *= 1.0 - q_weight;
+        }
+        for &(a, q) in &self.child_values {
+            if let Some(t) = target.get_mut(a) {
+                *t += q_weight * (q - max).exp() / sum;
+            }
+        }
+        target
+    }
 
-    for _ in 0..cfg.simulations {
-        simulate(&mut root, cfg.c_puct, eval);
+    /// Machine-readable summary as a single JSON object.
+    pub fn to_json(&self) -> String {
+        let children = self
+            .child_visits
+            .iter()
+            .zip(&self.child_values)
+            .map(|(&(a, n), &(_, q))| {
+                Value::Object(vec![
+                    ("action".into(), a.into()),
+                    ("visits".into(), n.into()),
+                    ("mean_value".into(), q.into()),
+                ])
+            })
+            .collect();
+        Value::Object(vec![
+            ("chosen_action".into(), self.chosen_action.into()),
+            ("root_visits".into(), self.root_visits.into()),
+            ("root_value".into(), self.root_value.into()),
+            ("simulations".into(), self.simulations.into()),
+            (
+                "elapsed_ms".into(),
+                (self.elapsed.as_secs_f64() * 1e3).into(),
+            ),
+            ("children".into(), Value::Array(children)),
+        ])
+        .to_string()
     }
+}
 
+/// Run MCTS and return the action [`SearchConfig::selection`] picks, by
+/// default the most visited.
+pub fn mcts_search<E: Evaluator>(root_state: &State, cfg: SearchConfig, eval: &E) -> SearchReport {
+    Searcher::new(root_state, cfg, eval).search()
+}
+
+/// Summarize the root after `simulations` simulations started at `start`.
+pub(crate) fn build_report(
+    root: &Node,
+    simulations: u32,
+    selection: SelectionPolicy,
+    start: Instant,
+) -> SearchReport {
     // Choose action by visit count at root
-    let mut best_action = None;
-    let mut best_visits = 0u32;
     let mut stats = Vec::new();
+    let mut values = Vec::new();
+    let mut candidates = Vec::new();
 
     for ch in &root.children {
-        // Derive which action produced this child
-        let mut action: Option<usize> = None;
-        for a in root_state.legal_moves() {
-            if let Some(s) = root_state.child_after_move(a)
-                && s == ch.state
-            {
-                action = Some(a);
-                break;
-            }
-        }
-        let a = action.unwrap_or(usize::MAX);
+        // the pit itself: two moves can lead to the same position
+        let a = ch.state.last_move().unwrap_or(usize::MAX);
+        let q = root.child_q(ch);
         stats.push((a, ch.visits));
-        if ch.visits > best_visits {
-            best_visits = ch.visits;
-            best_action = Some(a);
-        }
+        values.push((a, q));
+        candidates.push((a, ch.visits, q));
     }
 
+    let chosen_action = select_action(&candidates, selection);
+    let chosen_state = chosen_action.and_then(|a| {
+        let i = stats.iter().position(|&(b, _)| b == a)?;
+        Some(root.children[i].state.clone())
+    });
     SearchReport {
-        chosen_action: best_action,
+        chosen_action,
+        chosen_state,
         root_visits: root.visits,
+        root_value: root.value_mean(),
         child_visits: stats,
+        child_values: values,
+        simulations,
+        elapsed: start.elapsed(),
     }
 }
 
-/// One simulation.
-fn simulate<E: Evaluator>(root: &mut Node, c_puct: f32, eval: &E) {
+/// Pick from `(action, visits, mean value)` root stats under `policy`.
+/// Unvisited children never win.
+fn select_action(candidates: &[(usize, u32, f32)], policy: SelectionPolicy) -> Option<usize> {
+    let max_visits = candidates.iter().map(|c| c.1).max().unwrap_or(0);
+    let floor = match policy {
+        SelectionPolicy::VisitsThenValue => max_visits.div_ceil(2).max(1),
+        _ => 1,
+    };
+    let by_visits =
+        |x: &(usize, u32, f32), y: &(usize, u32, f32)| x.1.cmp(&y.1).then(x.2.total_cmp(&y.2));
+    let by_value =
+        |x: &(usize, u32, f32), y: &(usize, u32, f32)| x.2.total_cmp(&y.2).then(x.1.cmp(&y.1));
+    candidates
+        .iter()
+        .filter(|&&(_, n, _)| n >= floor)
+        .max_by(|x, y| {
+            match policy {
+                SelectionPolicy::MostVisits => by_visits(x, y),
+                SelectionPolicy::HighestValue | SelectionPolicy::VisitsThenValue => by_value(x, y),
+            }
+            .then(y.0.cmp(&x.0))
+        })
+        .map(|&(a, _, _)| a)
+}
+
+/// One simulation. With `tt`, selection reads and backpropagation also
+/// updates the shared per-position totals. Without `grow`, no node is
+/// added and selection continues past partly expanded nodes. Returns
+/// whether a node was added.
+pub(crate) fn simulate<E: Evaluator>(
+    root: &mut Node,
+    cfg: &SearchConfig,
+    eval: &E,
+    mut tt: Option<&mut TranspositionTable>,
+    grow: bool,
+) -> bool {
+    if cfg.ensure_root_breadth {
+        root.cover_legal_moves();
+    }
+
     // Selection
     let mut path: Vec<*mut Node> = Vec::with_capacity(64);
     let mut node: *mut Node = root as *mut Node;
+    let us = root.to_move;
 
     unsafe {
         path.push(node);
         while !(*node).is_terminal() {
-            if !(*node).unexpanded.is_empty() {
+            if grow && !(*node).unexpanded.is_empty() {
                 break;
             }
             if (*node).children.is_empty() {
                 break;
             }
-            let i = (*node).best_child(c_puct);
+            let unvisited = if cfg.ensure_root_breadth && path.len() == 1 {
+                (*node).unvisited_child()
+            } else {
+                None
+            };
+            let i = match (unvisited, &tt) {
+                (Some(i), _) => i,
+                (None, Some(tt)) => (*node).best_child_shared(cfg.c_puct, tt),
+                (None, None) => (*node).best_child(cfg.c_puct),
+            };
             node = &mut (&mut (*node).children)[i] as *mut Node;
             path.push(node);
         }
 
         // Expansion → Evaluate
-        let value = if !(*node).is_terminal() && !(*node).unexpanded.is_empty() {
-            if let Some(i) = (*node).expand(eval) {
-                node = &mut (&mut (*node).children)[i] as *mut Node;
-                path.push(node);
-            }
-            evaluate_leaf(&*node, eval)
-        } else {
-            evaluate_leaf(&*node, eval)
-        };
+        let mut grew = false;
+        if grow
+            && !(*node).is_terminal()
+            && !(*node).unexpanded.is_empty()
+            && let Some(i) = (*node).expand(eval)
+        {
+            node = &mut (&mut (*node).children)[i] as *mut Node;
+            path.push(node);
+            grew = true;
+        }
+        let value = evaluate_leaf(&*node, us, cfg, eval);
 
-        // Backpropagation (flip sign only when the turn switches)
+        // Backpropagation (flip sign only when the turn switches, discount
+        // once per ply)
         let mut v = value;
         for i in (0..path.len()).rev() {
             let node_i = path[i];
             (*node_i).visits += 1;
             (*node_i).value_sum += v;
+            if let Some(tt) = tt.as_deref_mut() {
+                tt.record(&(*node_i).state, v);
+            }
 
             if i > 0 {
                 let parent = path[i - 1];
                 if (*parent).to_move != (*node_i).to_move {
                     v = -v;
                 }
+                v *= cfg.discount;
             }
         }
+        grew
     }
 }
 
-/// Evaluate a leaf: terminal → exact, else evaluator.value.
-fn evaluate_leaf<E: Evaluator>(n: &Node, eval: &E) -> f32 {
-    if n.is_terminal() {
+/// Evaluate a leaf: terminal → exact (draws per `cfg.draw_value` for `us`),
+/// else evaluator.value.
+fn evaluate_leaf<E: Evaluator>(n: &Node, us: Player, cfg: &SearchConfig, eval: &E) -> f32 {
+    if n.is_terminal() || n.state.is_stalemate() {
         match n.state.outcome() {
-            Outcome::Win(p) if p == n.to_move => -1.0,
-            Outcome::Win(_) => 1.0,
-            Outcome::Draw => 0.0,
+            Outcome::Win(p) if p == n.to_move => 1.0,
+            Outcome::Win(_) => -1.0,
+            Outcome::Draw if n.to_move == us => cfg.draw_value,
+            Outcome::Draw => -cfg.draw_value,
             Outcome::Ongoing => 0.0,
         }
     } else {
-        let (_pi, v) = eval.policy_value(&n.state);
-        v
+        eval.value(&n.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+    use crate::json;
+    use mancala::StateBuilder;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::cell::Cell;
+
+    /// A to move. Pit 1 leads to a draw however both sides play; pit 0
+    /// keeps wins and losses on the table.
+    fn draw_or_gamble() -> State {
+        StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::A, 1, 1)
+            .pit(Player::B, 0, 1)
+            .pit(Player::B, 3, 1)
+            .build()
+            .unwrap()
+    }
+
+    fn pick(draw_value: f32) -> Option<usize> {
+        let cfg = SearchConfig {
+            simulations: 1_000,
+            draw_value,
+            ..Default::default()
+        };
+        mcts_search(&draw_or_gamble(), cfg, &RandomEvaluator::new(16)).chosen_action
+    }
+
+    #[test]
+    fn tie_on_visits_prefers_higher_value() {
+        let c = [(0, 10, 0.1), (3, 10, 0.4), (5, 7, 0.9)];
+        assert_eq!(select_action(&c, SelectionPolicy::MostVisits), Some(3));
+    }
+
+    #[test]
+    fn tie_on_visits_and_value_prefers_lower_pit() {
+        let c = [(4, 10, 0.2), (1, 10, 0.2), (2, 3, 0.2)];
+        assert_eq!(select_action(&c, SelectionPolicy::MostVisits), Some(1));
+    }
+
+    #[test]
+    fn selection_policies_disagree_when_value_and_visits_do() {
+        // 1 is most visited, 4 has the best value on few visits, 2 is a
+        // well explored runner-up
+        let c = [(1, 40, 0.1), (2, 25, 0.3), (4, 3, 0.8)];
+        assert_eq!(select_action(&c, SelectionPolicy::MostVisits), Some(1));
+        assert_eq!(select_action(&c, SelectionPolicy::HighestValue), Some(4));
+        assert_eq!(select_action(&c, SelectionPolicy::VisitsThenValue), Some(2));
+    }
+
+    #[test]
+    fn sampling_follows_visits_and_collapses_at_zero_temperature() {
+        let mut r = report(&[(0, 30, 0.1), (2, 10, 0.3), (5, 0, 0.9)]);
+        r.chosen_action = Some(0);
+        let mut rng = StdRng::seed_from_u64(3);
+        assert_eq!(r.sample_action(0.0, &mut rng), Some(0));
+        let mut counts = [0; PITS_PER_SIDE];
+        for _ in 0..400 {
+            counts[r.sample_action(1.0, &mut rng).unwrap()] += 1;
+        }
+        assert_eq!(counts[5], 0);
+        assert!(counts[0] > 2 * counts[2] && counts[2] > 0, "{counts:?}");
+        assert_eq!(report(&[(1, 0, 0.0)]).sample_action(1.0, &mut rng), None);
+    }
+
+    #[test]
+    fn no_visits_means_no_action() {
+        for policy in [
+            SelectionPolicy::MostVisits,
+            SelectionPolicy::HighestValue,
+            SelectionPolicy::VisitsThenValue,
+        ] {
+            assert_eq!(select_action(&[(0, 0, 0.0), (1, 0, 0.9)], policy), None);
+            assert_eq!(select_action(&[], policy), None);
+        }
+    }
+
+    #[test]
+    fn report_json_has_expected_keys() {
+        let cfg = SearchConfig {
+            simulations: 200,
+            ..Default::default()
+        };
+        let report = mcts_search(&State::new(), cfg, &RandomEvaluator::new(64));
+        let v = json::parse(&report.to_json()).unwrap();
+        for key in [
+            "chosen_action",
+            "root_visits",
+            "root_value",
+            "simulations",
+            "elapsed_ms",
+            "children",
+        ] {
+            assert!(v.get(key).is_some(), "missing {key}");
+        }
+        assert_eq!(v.get("simulations").unwrap().as_f64(), Some(200.0));
+        assert_eq!(
+            v.get("chosen_action").unwrap().as_f64(),
+            report.chosen_action.map(|a| a as f64)
+        );
+        let children = v.get("children").unwrap().as_array().unwrap();
+        assert_eq!(children.len(), report.child_visits.len());
+        for c in children {
+            assert!(c.get("action").is_some());
+            assert!(c.get("visits").is_some());
+            assert!(c.get("mean_value").is_some());
+        }
+    }
+
+    fn report(children: &[(usize, u32, f32)]) -> SearchReport {
+        SearchReport {
+            chosen_action: None,
+            chosen_state: None,
+            root_visits: children.iter().map(|c| c.1).sum(),
+            root_value: 0.0,
+            child_visits: children.iter().map(|&(a, n, _)| (a, n)).collect(),
+            child_values: children.iter().map(|&(a, _, q)| (a, q)).collect(),
+            simulations: 0,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn blended_policy_target() {
+        let r = report(&[(0, 5, -0.5), (2, 5, 0.8), (4, 0, 0.0)]);
+        let visits = r.policy_target();
+        assert_eq!(visits, [0.5, 0.0, 0.5, 0.0, 0.0, 0.0]);
+        assert_eq!(r.policy_target_blended(0.0), visits);
+
+        let blended = r.policy_target_blended(0.5);
+        assert!((blended.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!(blended[2] > visits[2] && blended[0] < visits[0]);
+        assert!(blended[4] > 0.0);
+
+        let cfg = SearchConfig {
+            simulations: 200,
+            ..Default::default()
+        };
+        let searched = mcts_search(&State::new(), cfg, &RandomEvaluator::new(16));
+        assert_eq!(
+            searched.policy_target_blended(0.0),
+            searched.policy_target()
+        );
+    }
+
+    #[test]
+    fn negative_draw_value_prefers_the_gamble() {
+        assert_eq!(pick(0.0), Some(1));
+        assert_eq!(pick(-1.0), Some(0));
+    }
+
+    /// Puts all its prior on the lowest legal pit and names no other move.
+    struct Narrow;
+
+    impl Evaluator for Narrow {
+        fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+            (
+                state
+                    .legal_moves()
+                    .into_iter()
+                    .take(1)
+                    .map(|a| (a, 1.0))
+                    .collect(),
+                0.0,
+            )
+        }
+    }
+
+    #[test]
+    fn root_breadth_visits_every_legal_move() {
+        let cfg = SearchConfig {
+            simulations: PITS_PER_SIDE as u32,
+            ..Default::default()
+        };
+        let r = mcts_search(&State::new(), cfg, &Narrow);
+        assert_eq!(r.child_visits.len(), PITS_PER_SIDE);
+        assert!(r.child_visits.iter().all(|&(_, n)| n >= 1));
+
+        let narrow = SearchConfig {
+            ensure_root_breadth: false,
+            ..cfg
+        };
+        let r = mcts_search(&State::new(), narrow, &Narrow);
+        assert_eq!(r.child_visits.len(), 1);
+    }
+
+    #[test]
+    fn chosen_state_follows_chosen_action() {
+        let cfg = SearchConfig {
+            simulations: 200,
+            ..Default::default()
+        };
+        let root = State::new().child_after_move(2).unwrap();
+        let r = mcts_search(&root, cfg, &RandomEvaluator::new(16));
+        let a = r.chosen_action.unwrap();
+        assert_eq!(r.chosen_state, root.child_after_move(a));
+    }
+
+    #[test]
+    fn prepare_runs_once_per_search() {
+        struct Prepared(Cell<u32>);
+
+        impl Evaluator for Prepared {
+            fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+                assert!(self.0.get() > 0, "evaluated before prepare");
+                RandomEvaluator::new(8).policy_value(state)
+            }
+
+            fn prepare(&self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let eval = Prepared(Cell::new(0));
+        let cfg = SearchConfig {
+            simulations: 50,
+            ..Default::default()
+        };
+        mcts_search(&State::new(), cfg, &eval);
+        assert_eq!(eval.0.get(), 1);
+        mcts_search(&State::new(), cfg, &eval);
+        assert_eq!(eval.0.get(), 2);
+    }
+
+    /// Counts which of its entry points the search used.
+    #[derive(Default)]
+    struct Split {
+        both: Cell<u32>,
+        value: Cell<u32>,
+        policy: Cell<u32>,
+    }
+
+    impl Evaluator for Split {
+        fn policy_value(&self, state: &State) -> (Vec<(usize, f32)>, f32) {
+            self.both.set(self.both.get() + 1);
+            (Narrow.policy(state), 0.0)
+        }
+
+        fn value(&self, _state: &State) -> f32 {
+            self.value.set(self.value.get() + 1);
+            0.0
+        }
+
+        fn policy(&self, state: &State) -> Vec<(usize, f32)> {
+            self.policy.set(self.policy.get() + 1);
+            Narrow.policy(state)
+        }
+    }
+
+    #[test]
+    fn stalemate_root_backs_up_the_store_result() {
+        // A's only move would take all of B's stones, which is forbidden
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 4, 3)
+            .store(Player::A, 23)
+            .store(Player::B, 21)
+            .rules(mancala::Rules {
+                grand_slam: mancala::GrandSlam::Forbidden,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let cfg = SearchConfig {
+            simulations: 10,
+            ..Default::default()
+        };
+        let r = mcts_search(&s, cfg, &Split::default());
+        assert_eq!(r.chosen_action, None);
+        assert_eq!(r.root_value, 1.0);
+    }
+
+    #[test]
+    fn search_uses_value_and_policy_separately() {
+        let eval = Split::default();
+        let cfg = SearchConfig {
+            simulations: 50,
+            ..Default::default()
+        };
+        mcts_search(&State::new(), cfg, &eval);
+        assert_eq!(eval.both.get(), 0);
+        assert!(eval.value.get() > 0);
+        assert!(eval.policy.get() > 0);
+    }
+
+    #[test]
+    fn discount_prefers_the_quicker_win() {
+        // A leads by far. Pit 4 captures B's last stone and ends the game at
+        // once; pit 0 lets B capture back, which also ends it, one ply later.
+        let s = StateBuilder::new()
+            .pits(Player::A, [1, 0, 0, 0, 1, 0])
+            .pits(Player::B, [1, 0, 0, 0, 0, 0])
+            .store(Player::A, 40)
+            .store(Player::B, 5)
+            .build()
+            .unwrap();
+        let cfg = SearchConfig {
+            simulations: 400,
+            discount: 0.9,
+            ..Default::default()
+        };
+        let r = mcts_search(&s, cfg, &RandomEvaluator::new(8));
+        let q = |a| r.child_values.iter().find(|&&(b, _)| b == a).unwrap().1;
+        assert!((q(4) - 1.0).abs() < 1e-6);
+        // pit 0's first visit is the evaluator's undiscounted estimate, the
+        // rest back up 0.9
+        assert!(q(0) < q(4) && q(0) >= 0.9 - 1e-6);
+        assert_eq!(r.chosen_action, Some(4));
+    }
+
+    #[test]
+    fn symmetry_error_compares_interchangeable_moves() {
+        // pits 1 and 2 both end the game in the same final position
+        let s = State::from_fen("0,2,1,0,0,9/0,0,1,0,0,0 15 20 A").unwrap();
+        assert_eq!(
+            s.child_after_move(1).unwrap().canonical_key(),
+            s.child_after_move(2).unwrap().canonical_key()
+        );
+        let cfg = SearchConfig {
+            simulations: 300,
+            ..Default::default()
+        };
+        // equal priors and equal results: the search has to split 1 and 2
+        let uniform = mcts_search(&s, cfg, &RandomEvaluator::new(16));
+        let mut actions: Vec<usize> = uniform.child_visits.iter().map(|&(a, _)| a).collect();
+        actions.sort_unstable();
+        assert_eq!(actions, [1, 2, 5]);
+        assert!(
+            uniform.symmetry_error(&s) < 0.05,
+            "{:?}",
+            uniform.child_visits
+        );
+        // a prior on pit 1 alone leaves pit 2 with its one breadth visit
+        let narrow = mcts_search(&s, cfg, &Narrow);
+        assert!(narrow.symmetry_error(&s) > 0.9, "{:?}", narrow.child_visits);
+
+        let even = report(&[(1, 4, 0.0), (2, 4, 0.0), (5, 2, 0.0)]);
+        assert!(even.symmetry_error(&s) < 1e-6);
+        let skewed = report(&[(1, 8, 0.0), (2, 0, 0.0), (5, 2, 0.0)]);
+        assert!((skewed.symmetry_error(&s) - 0.8).abs() < 1e-6);
     }
 }
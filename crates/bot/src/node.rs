@@ -1,6 +1,7 @@
 use mancala::{Player, State};
 
 use super::evaluator::Evaluator;
+use super::transposition::TranspositionTable;
 
 /// Single MCTS node (PUCT).
 #[derive(Clone)]
@@ -58,24 +59,49 @@ impl Node {
         }
     }
 
-    /// PUCT score: Q + c_puct * P * sqrt(N) / (1 + n)
-    pub fn ucb(&self, child: &Node, c_puct: f32) -> f32 {
-        let q_parent = if self.to_move == child.to_move {
+    /// Mean value of `child` from this node's side to move.
+    #[inline]
+    pub fn child_q(&self, child: &Node) -> f32 {
+        if self.to_move == child.to_move {
             child.value_mean()
         } else {
             -child.value_mean()
-        };
+        }
+    }
 
-        let n = child.visits as f32;
-        let n_parent = self.visits.max(1) as f32;
-        q_parent + c_puct * child.prior * (n_parent.sqrt() / (1.0 + n))
+    /// PUCT score: Q + c_puct * P * sqrt(N) / (1 + n)
+    pub fn ucb(&self, child: &Node, c_puct: f32) -> f32 {
+        let q_parent = self.child_q(child);
+        puct(q_parent, child.prior, child.visits, self.visits, c_puct)
     }
 
     pub fn best_child(&self, c_puct: f32) -> usize {
+        self.argmax(|ch| self.ucb(ch, c_puct))
+    }
+
+    /// [`best_child`](Self::best_child) scoring each position by its totals
+    /// in `tt` (falling back to the node's own), so transpositions reached
+    /// along other paths count too.
+    pub(crate) fn best_child_shared(&self, c_puct: f32, tt: &TranspositionTable) -> usize {
+        let stats = |n: &Node| tt.get(&n.state).unwrap_or((n.visits, n.value_sum));
+        let (n_parent, _) = stats(self);
+        self.argmax(|ch| {
+            let (n, sum) = stats(ch);
+            let mean = if n == 0 { 0.0 } else { sum / n as f32 };
+            let q = if self.to_move == ch.to_move {
+                mean
+            } else {
+                -mean
+            };
+            puct(q, ch.prior, n, n_parent, c_puct)
+        })
+    }
+
+    fn argmax(&self, score: impl Fn(&Node) -> f32) -> usize {
         let mut best = 0usize;
         let mut best_score = f32::NEG_INFINITY;
         for (i, ch) in self.children.iter().enumerate() {
-            let s = self.ucb(ch, c_puct);
+            let s = score(ch);
             if s > best_score {
                 best_score = s;
                 best = i;
@@ -84,6 +110,33 @@ impl Node {
         best
     }
 
+    /// Add any legal move missing from both `children` and `unexpanded`
+    /// (because the evaluator's policy left it out) with a zero prior.
+    /// Expansion still reaches such moves, after the ones with weight.
+    pub(crate) fn cover_legal_moves(&mut self) {
+        let legal = self.state.legal_moves();
+        if self.children.len() + self.unexpanded.len() >= legal.len() {
+            return;
+        }
+        for (a, next) in self.state.legal_transitions() {
+            let known = self.unexpanded.iter().any(|&(m, _)| m == a)
+                || self.children.iter().any(|c| c.state == next);
+            if !known {
+                self.unexpanded.push((a, 0.0));
+            }
+        }
+    }
+
+    /// Nodes in this subtree, itself included.
+    pub fn subtree_size(&self) -> usize {
+        1 + self.children.iter().map(Node::subtree_size).sum::<usize>()
+    }
+
+    /// Index of the first child that has never been visited.
+    pub(crate) fn unvisited_child(&self) -> Option<usize> {
+        self.children.iter().position(|c| c.visits == 0)
+    }
+
     /// Expand one child using evaluator priors. Returns new child index.
     pub fn expand<E: Evaluator>(&mut self, eval: &E) -> Option<usize> {
         use rand::{distr::weighted::WeightedIndex, prelude::*};
@@ -97,10 +150,16 @@ impl Node {
         let dist = WeightedIndex::new(weights.iter().cloned().map(|w| w.max(1e-6))).ok()?;
         let mut rng = rand::rng();
         let idx = dist.sample(&mut rng);
+        Some(self.push_child(idx, eval))
+    }
+
+    /// Move `unexpanded[idx]` into `children` as a fresh node. Returns its
+    /// child index.
+    pub(crate) fn push_child<E: Evaluator>(&mut self, idx: usize, eval: &E) -> usize {
         let (action, prior) = self.unexpanded.swap_remove(idx);
 
         let child_state = self.state.child_after_move(action).unwrap();
-        let (child_priors, _v) = eval.policy_value(&child_state);
+        let child_priors = eval.policy(&child_state);
 
         let to_move = child_state.current_player();
         let mut child = Node {
@@ -115,6 +174,13 @@ impl Node {
         child.normalize_priors_if_needed();
 
         self.children.push(child);
-        Some(self.children.len() - 1)
+        self.children.len() - 1
     }
 }
+
+#[inline]
+fn puct(q: f32, prior: f32, visits: u32, parent_visits: u32, c_puct: f32) -> f32 {
+    let n = visits as f32;
+    let n_parent = parent_visits.max(1) as f32;
+    q + c_puct * prior * (n_parent.sqrt() / (1.0 + n))
+}
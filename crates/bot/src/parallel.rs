@@ -0,0 +1,94 @@
+//! Root-parallel MCTS: independent trees on several threads, merged at the
+//! root.
+
+use std::thread;
+use std::time::Instant;
+
+use mancala::State;
+
+use super::evaluator::SharedEvaluator;
+use super::mcts::{SearchConfig, SearchReport, build_report};
+use super::searcher::Searcher;
+
+/// Split `cfg.simulations` over `threads` independent searches sharing
+/// `eval`, then sum their root children's statistics into one report.
+/// `threads` of 0 is treated as 1.
+pub fn mcts_search_parallel(
+    state: &State,
+    cfg: SearchConfig,
+    eval: SharedEvaluator,
+    threads: usize,
+) -> SearchReport {
+    let start = Instant::now();
+    let threads = threads.max(1) as u32;
+    let per = cfg.simulations / threads;
+    let extra = cfg.simulations % threads;
+    eval.prepare();
+
+    let stats: Vec<_> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|i| {
+                let cfg = SearchConfig {
+                    simulations: per + u32::from(i < extra),
+                    ..cfg
+                };
+                let eval = eval.clone();
+                scope.spawn(move || {
+                    let mut s = Searcher::prepared(state, cfg, eval);
+                    s.search();
+                    s.export_stats()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().expect("search thread panicked"))
+            .collect()
+    });
+
+    let mut merged = Searcher::prepared(state, cfg, eval);
+    for s in &stats {
+        merged.import_stats(s);
+    }
+    build_report(merged.root(), cfg.simulations, cfg.selection, start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Evaluator, RandomEvaluator};
+    use std::sync::Arc;
+
+    #[test]
+    fn evaluator_is_shared_across_threads() {
+        let eval: SharedEvaluator = Arc::new(RandomEvaluator::new(64));
+        let s = State::new();
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                let eval = eval.clone();
+                let s = &s;
+                scope.spawn(move || {
+                    let (policy, v) = eval.policy_value(s);
+                    assert_eq!(policy.len(), s.legal_moves().len());
+                    assert!((-1.0..=1.0).contains(&v));
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn parallel_search_merges_root_statistics() {
+        let cfg = SearchConfig {
+            simulations: 401,
+            ..Default::default()
+        };
+        let s = State::new();
+        let r = mcts_search_parallel(&s, cfg, Arc::new(RandomEvaluator::new(32)), 4);
+        let m = r.chosen_action.unwrap();
+        assert!(s.legal_moves().contains(&m));
+        let child_visits: u32 = r.child_visits.iter().map(|&(_, n)| n).sum();
+        assert_eq!(child_visits, 401);
+        assert_eq!(r.root_visits, 401);
+        assert_eq!(r.simulations, 401);
+    }
+}
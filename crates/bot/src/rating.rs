@@ -0,0 +1,97 @@
+//! Elo-scale strength estimates from tournament results.
+
+use std::collections::HashMap;
+
+use super::tournament::TournamentResult;
+
+/// Fit Bradley-Terry strengths to `results` and report them on the Elo
+/// scale (400 points is 10:1 odds), centered on a mean of zero.
+///
+/// A draw counts as half a win for each side. Every pair that met also
+/// gets one virtual draw, which keeps ratings finite for players who never
+/// won (or never lost).
+pub fn estimate(results: &TournamentResult) -> HashMap<String, f64> {
+    const ITERATIONS: usize = 200;
+
+    let n = results.players.len();
+    // wins[i]: score of i; games[i][j]: games between i and j
+    let mut wins = vec![0.0f64; n];
+    let mut games = vec![vec![0.0f64; n]; n];
+    for p in &results.pairs {
+        if p.games() == 0 {
+            continue;
+        }
+        let draws = p.draws as f64 + 1.0;
+        wins[p.a] += p.wins_a as f64 + draws / 2.0;
+        wins[p.b] += p.wins_b as f64 + draws / 2.0;
+        let played = p.games() as f64 + 1.0;
+        games[p.a][p.b] += played;
+        games[p.b][p.a] += played;
+    }
+
+    // minorization-maximization (Hunter 2004)
+    let mut gamma = vec![1.0f64; n];
+    for _ in 0..ITERATIONS {
+        for i in 0..n {
+            let denom: f64 = (0..n)
+                .filter(|&j| games[i][j] > 0.0)
+                .map(|j| games[i][j] / (gamma[i] + gamma[j]))
+                .sum();
+            if denom > 0.0 {
+                gamma[i] = wins[i] / denom;
+            }
+        }
+        let log_mean = gamma.iter().map(|g| g.ln()).sum::<f64>() / n.max(1) as f64;
+        for g in &mut gamma {
+            *g /= log_mean.exp();
+        }
+    }
+
+    results
+        .players
+        .iter()
+        .zip(gamma)
+        .map(|(name, g)| (name.clone(), 400.0 * g.log10()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tournament::PairRecord;
+
+    fn pair(a: usize, b: usize, wins_a: u32, draws: u32, wins_b: u32) -> PairRecord {
+        PairRecord {
+            a,
+            b,
+            wins_a,
+            draws,
+            wins_b,
+        }
+    }
+
+    #[test]
+    fn dominant_player_rates_highest() {
+        let results = TournamentResult {
+            players: vec!["top".into(), "mid".into(), "low".into()],
+            pairs: vec![
+                pair(0, 1, 8, 0, 0),
+                pair(0, 2, 8, 0, 0),
+                pair(1, 2, 5, 1, 2),
+            ],
+        };
+        let r = estimate(&results);
+        assert_eq!(r.len(), 3);
+        assert!(r.values().all(|v| v.is_finite()));
+        assert!(r["top"] > r["mid"] && r["mid"] > r["low"]);
+        assert!(r.values().sum::<f64>().abs() < 1e-6);
+
+        // evenly matched players end up level
+        let even = TournamentResult {
+            players: vec!["p".into(), "q".into()],
+            pairs: vec![pair(0, 1, 3, 2, 3)],
+        };
+        let r = estimate(&even);
+        assert!((r["p"] - r["q"]).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,204 @@
+//! Append-only binary game records.
+//!
+//! Each game is the start position's [`State::to_bytes`] blob, a LEB128
+//! varint move count, then one byte per move (the pit index).
+
+use std::io::{self, ErrorKind, Read, Write};
+
+use mancala::{PITS_PER_SIDE, STATE_BYTES, State};
+
+/// A game as its start position and the pits played from it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord {
+    pub start: State,
+    pub moves: Vec<usize>,
+}
+
+impl GameRecord {
+    /// The position after every move, or `None` if one is illegal.
+    pub fn final_state(&self) -> Option<State> {
+        self.moves
+            .iter()
+            .try_fold(self.start.clone(), |s, &m| s.child_after_move(m))
+    }
+}
+
+/// Appends games to a byte sink.
+pub struct GameWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> GameWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Write one game. Fails with `InvalidInput` on a pit index that is not
+    /// below `PITS_PER_SIDE`.
+    pub fn write_game(&mut self, start: &State, moves: &[usize]) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(STATE_BYTES + 10 + moves.len());
+        buf.extend_from_slice(&start.to_bytes());
+        let mut n = moves.len() as u64;
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                buf.push(byte);
+                break;
+            }
+            buf.push(byte | 0x80);
+        }
+        for &m in moves {
+            if m >= PITS_PER_SIDE {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("pit index {m} out of bounds"),
+                ));
+            }
+            buf.push(m as u8);
+        }
+        self.inner.write_all(&buf)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Iterates `(start position, moves)` back out of a [`GameWriter`] stream.
+///
+/// Ends cleanly at end of input between games; a truncated or corrupt game
+/// yields an error.
+pub struct GameReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> GameReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    fn read_game(&mut self) -> io::Result<Option<(State, Vec<usize>)>> {
+        let mut blob = [0; STATE_BYTES];
+        // a clean end of stream is only allowed before the first byte
+        loop {
+            match self.inner.read(&mut blob[..1]) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        self.inner.read_exact(&mut blob[1..])?;
+        let start = State::from_bytes(&blob).map_err(|e| invalid(e.to_string()))?;
+
+        let mut len = 0u64;
+        for shift in (0..64).step_by(7) {
+            let mut b = [0];
+            self.inner.read_exact(&mut b)?;
+            len |= ((b[0] & 0x7f) as u64) << shift;
+            if b[0] & 0x80 == 0 {
+                break;
+            }
+            if shift + 7 >= 64 {
+                return Err(invalid("move count overflows".into()));
+            }
+        }
+
+        // grow as data arrives rather than trusting a corrupt count
+        let mut raw = Vec::new();
+        (&mut self.inner).take(len).read_to_end(&mut raw)?;
+        if (raw.len() as u64) < len {
+            return Err(ErrorKind::UnexpectedEof.into());
+        }
+        let moves = raw
+            .into_iter()
+            .map(|m| {
+                let m = m as usize;
+                if m < PITS_PER_SIDE {
+                    Ok(m)
+                } else {
+                    Err(invalid(format!("pit index {m} out of bounds")))
+                }
+            })
+            .collect::<io::Result<_>>()?;
+        Ok(Some((start, moves)))
+    }
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+impl<R: Read> Iterator for GameReader<R> {
+    type Item = io::Result<(State, Vec<usize>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_game().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn random_game(seed: u64) -> (State, Vec<usize>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start = State::new();
+        let mut s = start.clone();
+        let mut moves = Vec::new();
+        while let Some((m, next)) = s.apply_random_move(&mut rng) {
+            moves.push(m);
+            s = next;
+        }
+        (start, moves)
+    }
+
+    #[test]
+    fn round_trips_several_games() {
+        let mut games: Vec<_> = (0..5).map(random_game).collect();
+        // a long move list needs a multi-byte count
+        games.push((State::new(), vec![1; 300]));
+        games.push((State::new().child_after_move(2).unwrap(), Vec::new()));
+
+        let mut w = GameWriter::new(Vec::new());
+        for (start, moves) in &games {
+            w.write_game(start, moves).unwrap();
+        }
+        let bytes = w.into_inner();
+
+        let back: Vec<_> = GameReader::new(bytes.as_slice())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(back.len(), games.len());
+        for ((s0, m0), (s1, m1)) in games.iter().zip(&back) {
+            assert!(s0 == s1);
+            assert_eq!(m0, m1);
+        }
+    }
+
+    #[test]
+    fn truncated_game_is_an_error() {
+        let (start, moves) = random_game(9);
+        let mut w = GameWriter::new(Vec::new());
+        w.write_game(&start, &moves).unwrap();
+        let bytes = w.into_inner();
+
+        let mut r = GameReader::new(&bytes[..bytes.len() - 1]);
+        let err = r.next().unwrap().err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn rejects_out_of_range_move() {
+        let mut w = GameWriter::new(Vec::new());
+        let err = w.write_game(&State::new(), &[PITS_PER_SIDE]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,117 @@
+//! Fixed-capacity replay buffer of training samples.
+
+use std::collections::VecDeque;
+
+use mancala::State;
+use rand::Rng;
+use rand::seq::index;
+
+/// One training example: a position with its search policy and game result.
+#[derive(Clone)]
+pub struct Sample {
+    pub state: State,
+    /// (action, probability), as returned by [`Evaluator::policy_value`].
+    ///
+    /// [`Evaluator::policy_value`]: crate::Evaluator::policy_value
+    pub policy: Vec<(usize, f32)>,
+    /// Final result for the side to move in `state`, in `[-1, 1]`.
+    pub value: f32,
+}
+
+/// Ring buffer holding the most recent `capacity` samples.
+pub struct ReplayBuffer {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Add a sample, evicting the oldest once full.
+    pub fn push(&mut self, sample: Sample) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Up to `n` distinct samples chosen uniformly at random.
+    pub fn sample<R: Rng + ?Sized>(&self, n: usize, rng: &mut R) -> Vec<&Sample> {
+        let n = n.min(self.samples.len());
+        index::sample(rng, self.samples.len(), n)
+            .into_iter()
+            .map(|i| &self.samples[i])
+            .collect()
+    }
+
+    /// Samples from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Sample> {
+        self.samples.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn tagged(value: f32) -> Sample {
+        Sample {
+            state: State::new(),
+            policy: Vec::new(),
+            value,
+        }
+    }
+
+    #[test]
+    fn overflow_evicts_oldest() {
+        let mut buf = ReplayBuffer::new(3);
+        for i in 0..5 {
+            buf.push(tagged(i as f32));
+        }
+        assert_eq!(buf.len(), 3);
+        let kept: Vec<f32> = buf.iter().map(|s| s.value).collect();
+        assert_eq!(kept, vec![2.0, 3.0, 4.0]);
+    }
+
+    #[test]
+    fn full_buffer_returns_requested_count() {
+        let mut buf = ReplayBuffer::new(8);
+        for i in 0..8 {
+            buf.push(tagged(i as f32));
+        }
+        let mut rng = StdRng::seed_from_u64(7);
+        let batch = buf.sample(5, &mut rng);
+        assert_eq!(batch.len(), 5);
+        let mut seen: Vec<f32> = batch.iter().map(|s| s.value).collect();
+        seen.sort_by(f32::total_cmp);
+        seen.dedup();
+        assert_eq!(seen.len(), 5);
+
+        assert_eq!(buf.sample(20, &mut rng).len(), 8);
+    }
+}
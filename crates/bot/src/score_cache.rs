@@ -0,0 +1,106 @@
+//! Memoized scores for move ordering.
+
+use std::collections::HashMap;
+
+use mancala::{Player, State};
+
+/// Scores from `score`, computed once per position and player. `State`
+/// compares and hashes by position alone, so one position reached in
+/// different ways shares an entry.
+#[derive(Clone, Debug)]
+pub struct ScoreCache<F = fn(&State, Player) -> i32> {
+    score: F,
+    scores: HashMap<(State, Player), i32>,
+}
+
+impl ScoreCache {
+    /// Caches [`State::projected_score_for`].
+    pub fn projected() -> Self {
+        Self::new(State::projected_score_for)
+    }
+}
+
+impl<F: Fn(&State, Player) -> i32> ScoreCache<F> {
+    pub fn new(score: F) -> Self {
+        Self {
+            score,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// `score(state, player)`, computed on the first request only.
+    pub fn get(&mut self, state: &State, player: Player) -> i32 {
+        if let Some(&v) = self.scores.get(&(state.clone(), player)) {
+            return v;
+        }
+        let v = (self.score)(state, player);
+        self.scores.insert((state.clone(), player), v);
+        v
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Drop every cached score.
+    pub fn clear(&mut self) {
+        self.scores.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use mancala::{PITS_PER_SIDE, StateBuilder};
+
+    use super::*;
+
+    #[test]
+    fn each_state_is_scored_once() {
+        let calls = Cell::new(0);
+        let mut cache = ScoreCache::new(|s: &State, p| {
+            calls.set(calls.get() + 1);
+            s.projected_score_for(p)
+        });
+        let children = State::new().legal_actions();
+        for _ in 0..3 {
+            for c in &children {
+                assert_eq!(cache.get(c, Player::A), c.projected_score_for(Player::A));
+            }
+        }
+        assert_eq!(calls.get(), children.len());
+        assert_eq!(cache.len(), children.len());
+
+        // the same position reached by play and set up by hand shares an entry
+        let via = State::new().child_after_move(2).unwrap();
+        let played = via.child_after_move(0).unwrap();
+        let built = StateBuilder::new()
+            .pits(Player::A, [0, 5, 1, 6, 6, 5])
+            .pits(Player::B, [4; PITS_PER_SIDE])
+            .store(Player::A, 1)
+            .to_move(Player::B)
+            .build()
+            .unwrap();
+        assert!(played == built);
+        assert_ne!(played.last_move(), built.last_move());
+        cache.get(&played, Player::B);
+        let n = calls.get();
+        assert_eq!(
+            cache.get(&built, Player::B),
+            played.projected_score_for(Player::B)
+        );
+        assert_eq!(calls.get(), n);
+
+        let mut projected = ScoreCache::projected();
+        assert_eq!(
+            projected.get(&via, Player::B),
+            via.projected_score_for(Player::B)
+        );
+        assert_eq!(projected.len(), 1);
+    }
+}
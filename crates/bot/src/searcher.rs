@@ -0,0 +1,441 @@
+use std::time::Instant;
+
+use mancala::{Player, State};
+
+use super::dump::TreeDump;
+use super::evaluator::Evaluator;
+use super::mcts::{SearchConfig, SearchReport, build_report, simulate};
+use super::node::Node;
+use super::transposition::TranspositionTable;
+
+/// Accumulated statistics for one root child, keyed by its position.
+#[derive(Clone, Debug)]
+pub struct ChildStats {
+    pub state: State,
+    pub visits: u32,
+    /// Sum of backed-up values from `state`'s side to move.
+    pub value_sum: f32,
+}
+
+/// MCTS that keeps its tree between calls, so statistics carry over as the
+/// game advances and while pondering on the opponent's time.
+pub struct Searcher<E: Evaluator> {
+    root: Node,
+    cfg: SearchConfig,
+    eval: E,
+    tt: TranspositionTable,
+    /// `root.subtree_size()`, kept current without walking the tree.
+    nodes: usize,
+}
+
+impl<E: Evaluator> Searcher<E> {
+    /// Calls [`Evaluator::prepare`] once, before the root is evaluated.
+    pub fn new(state: &State, cfg: SearchConfig, eval: E) -> Self {
+        eval.prepare();
+        Self::prepared(state, cfg, eval)
+    }
+
+    /// [`new`](Self::new) for an evaluator that is already prepared.
+    pub(crate) fn prepared(state: &State, cfg: SearchConfig, eval: E) -> Self {
+        let root = fresh_root(state, &eval);
+        Self {
+            root,
+            cfg,
+            eval,
+            tt: TranspositionTable::new(),
+            nodes: 1,
+        }
+    }
+
+    #[inline]
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    #[inline]
+    pub fn state(&self) -> &State {
+        &self.root.state
+    }
+
+    #[inline]
+    pub fn config(&self) -> SearchConfig {
+        self.cfg
+    }
+
+    #[inline]
+    pub fn evaluator(&self) -> &E {
+        &self.eval
+    }
+
+    /// Nodes in the tree, root included.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.nodes
+    }
+
+    /// Shared per-position totals; empty unless `cfg.transpositions` is set.
+    /// Kept across [`advance`](Self::advance), since they stay valid.
+    #[inline]
+    pub fn transpositions(&self) -> &TranspositionTable {
+        &self.tt
+    }
+
+    /// Run `cfg.simulations` more simulations from the current root.
+    pub fn search(&mut self) -> SearchReport {
+        let start = Instant::now();
+        for _ in 0..self.cfg.simulations {
+            let tt = self.cfg.transpositions.then_some(&mut self.tt);
+            let grow = self.cfg.max_nodes.is_none_or(|cap| self.nodes < cap);
+            if simulate(&mut self.root, &self.cfg, &self.eval, tt, grow) {
+                self.nodes += 1;
+            }
+        }
+        build_report(&self.root, self.cfg.simulations, self.cfg.selection, start)
+    }
+
+    /// Move the root to `state`. Keeps the subtree if `state` is the root or
+    /// an explored child of it; otherwise discards the tree and starts fresh.
+    /// Returns whether existing statistics were kept.
+    pub fn advance(&mut self, state: &State) -> bool {
+        if self.root.state == *state {
+            return true;
+        }
+        let pos = self.root.children.iter().position(|c| c.state == *state);
+        match pos {
+            Some(i) => {
+                self.root = self.root.children.swap_remove(i);
+                self.nodes = self.root.subtree_size();
+                true
+            }
+            None => {
+                self.root = fresh_root(state, &self.eval);
+                self.nodes = 1;
+                false
+            }
+        }
+    }
+
+    /// Statistics of every explored root child, for [`import_stats`]
+    /// into a later search of the same position.
+    ///
+    /// [`import_stats`]: Self::import_stats
+    pub fn export_stats(&self) -> Vec<ChildStats> {
+        self.root
+            .children
+            .iter()
+            .map(|c| ChildStats {
+                state: c.state.clone(),
+                visits: c.visits,
+                value_sum: c.value_sum,
+            })
+            .collect()
+    }
+
+    /// Warm start: add `stats` to the matching root children, expanding
+    /// them first if needed, and credit the root with the same visits.
+    /// Entries that are not successors of the root are ignored.
+    pub fn import_stats(&mut self, stats: &[ChildStats]) {
+        for st in stats {
+            let existing = self.root.children.iter().position(|c| c.state == st.state);
+            let i = match existing {
+                Some(i) => i,
+                None => {
+                    let Some(a) = st.state.is_successor_of(&self.root.state) else {
+                        continue;
+                    };
+                    let Some(u) = self.root.unexpanded.iter().position(|&(m, _)| m == a) else {
+                        continue;
+                    };
+                    self.nodes += 1;
+                    self.root.push_child(u, &self.eval)
+                }
+            };
+            let child = &mut self.root.children[i];
+            child.visits += st.visits;
+            child.value_sum += st.value_sum;
+            let flip = if child.to_move == self.root.to_move {
+                1.0
+            } else {
+                -1.0
+            };
+            self.root.visits += st.visits;
+            self.root.value_sum += flip * st.value_sum;
+        }
+    }
+
+    /// Re-root to the opponent's `expected` reply and keep searching there.
+    /// A later [`advance`](Self::advance) to the actual position keeps the
+    /// pondered tree on a hit and rebuilds on a miss.
+    pub fn ponder_on(&mut self, expected: &State) {
+        self.advance(expected);
+        self.search();
+    }
+
+    /// A detached copy of the whole tree, for saving and offline inspection.
+    pub fn export_tree(&self) -> TreeDump {
+        TreeDump::from_node(&self.root, None)
+    }
+
+    /// The tree as a Graphviz `digraph`, down to `max_depth` plies below the
+    /// root. Nodes show the side to move, visits and mean value (for that
+    /// side); edges are labeled with the pit played.
+    pub fn to_dot(&self, max_depth: usize) -> String {
+        let mut out = String::from("digraph mcts {\n  node [shape=box];\n");
+        let mut next_id = 0;
+        dot_node(&self.root, max_depth, &mut next_id, &mut out);
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Write `node` and its subtree to `out`, returning the node's id.
+fn dot_node(node: &Node, depth_left: usize, next_id: &mut usize, out: &mut String) -> usize {
+    use std::fmt::Write;
+
+    let id = *next_id;
+    *next_id += 1;
+    // plain names: `Player`'s Display may add terminal colors
+    let side = match node.to_move {
+        Player::A => "A",
+        Player::B => "B",
+    };
+    let _ = writeln!(
+        out,
+        "  n{id} [label=\"{side} N={} Q={:.3}\"];",
+        node.visits,
+        node.value_mean()
+    );
+    if depth_left == 0 {
+        return id;
+    }
+    for child in &node.children {
+        let child_id = dot_node(child, depth_left - 1, next_id, out);
+        let pit = child
+            .state
+            .last_move()
+            .map_or("?".to_string(), |m| m.to_string());
+        let _ = writeln!(out, "  n{id} -> n{child_id} [label=\"{pit}\"];");
+    }
+    id
+}
+
+fn fresh_root<E: Evaluator>(state: &State, eval: &E) -> Node {
+    Node::new_root(state.clone(), &eval.policy(state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+    use mancala::StateBuilder;
+    use std::collections::HashMap;
+
+    fn cfg(simulations: u32) -> SearchConfig {
+        SearchConfig {
+            simulations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn max_nodes_caps_the_tree() {
+        let capped = SearchConfig {
+            max_nodes: Some(20),
+            ..cfg(500)
+        };
+        let mut s = Searcher::new(&State::new(), capped, RandomEvaluator::new(16));
+        let r = s.search();
+        assert_eq!(s.node_count(), s.root().subtree_size());
+        assert_eq!(s.node_count(), 20);
+        assert_eq!(r.root_visits, 500);
+        assert!(
+            State::new()
+                .legal_moves()
+                .contains(&r.chosen_action.unwrap())
+        );
+
+        let mut free = Searcher::new(&State::new(), cfg(50), RandomEvaluator::new(16));
+        free.search();
+        assert_eq!(free.node_count(), free.root().subtree_size());
+        assert!(free.advance(&free.root().children[0].state.clone()));
+        assert_eq!(free.node_count(), free.root().subtree_size());
+    }
+
+    #[test]
+    fn tree_dump_round_trips_through_json() {
+        let mut s = Searcher::new(&State::new(), cfg(300), RandomEvaluator::new(16));
+        s.search();
+        let dump = s.export_tree();
+        assert_eq!(dump.visits, s.root().visits);
+        assert_eq!(dump.action, None);
+        assert_eq!(dump.children.len(), s.root().children.len());
+        assert!(dump.node_count() > dump.children.len());
+
+        let back = TreeDump::from_json(&dump.to_json()).unwrap();
+        assert_eq!(back, dump);
+        assert_eq!(back.visits, s.root().visits);
+        let first = &s.root().children[0];
+        let a = first.state.last_move().unwrap();
+        assert_eq!(back.child(a).unwrap().visits, first.visits);
+
+        assert!(TreeDump::from_json("{\"state\": []}").is_err());
+    }
+
+    #[test]
+    fn dot_export_shows_root_and_respects_depth() {
+        let mut s = Searcher::new(&State::new(), cfg(300), RandomEvaluator::new(16));
+        s.search();
+        let dot = s.to_dot(1);
+        assert!(dot.starts_with("digraph mcts {") && dot.trim_end().ends_with('}'));
+        assert!(dot.contains(&format!("n0 [label=\"A N={} ", s.root().visits)));
+        let edges = dot.matches(" -> ").count();
+        assert_eq!(edges, s.root().children.len());
+        assert!(dot.contains("n0 -> n1 [label=\""));
+
+        assert_eq!(s.to_dot(0).matches(" -> ").count(), 0);
+        assert!(s.to_dot(3).matches(" -> ").count() > edges);
+    }
+
+    #[test]
+    fn search_accumulates_across_calls() {
+        let mut s = Searcher::new(&State::new(), cfg(50), RandomEvaluator::new(32));
+        s.search();
+        let r = s.search();
+        assert_eq!(r.root_visits, 100);
+        assert_eq!(r.simulations, 50);
+    }
+
+    #[test]
+    fn advance_reuses_explored_child() {
+        let mut s = Searcher::new(&State::new(), cfg(200), RandomEvaluator::new(32));
+        s.search();
+        let child = s.root().children[0].clone();
+        assert!(s.advance(&child.state));
+        assert!(*s.state() == child.state);
+        assert_eq!(s.root().visits, child.visits);
+    }
+
+    #[test]
+    fn ponder_accumulates_in_expected_subtree() {
+        // A has moved; B is to move and the searcher ponders on B's reply.
+        let after_a = State::new().child_after_move(0).unwrap();
+        let mut s = Searcher::new(&after_a, cfg(200), RandomEvaluator::new(32));
+        s.search();
+        let expected = s.root().children[0].clone();
+
+        s.ponder_on(&expected.state);
+        assert!(*s.state() == expected.state);
+        assert_eq!(s.root().visits, expected.visits + 200);
+
+        // Hit: the pondered tree is kept.
+        let pondered = s.root().visits;
+        assert!(s.advance(&expected.state));
+        assert_eq!(s.root().visits, pondered);
+    }
+
+    #[test]
+    fn ponder_miss_resets_cleanly() {
+        let after_a = State::new().child_after_move(0).unwrap();
+        let mut s = Searcher::new(&after_a, cfg(200), RandomEvaluator::new(32));
+        s.search();
+        let expected = after_a.child_after_move(0).unwrap();
+        let actual = after_a.child_after_move(1).unwrap();
+
+        s.ponder_on(&expected);
+        assert!(s.root().visits > 0);
+        assert!(!s.advance(&actual));
+        assert!(*s.state() == actual);
+        assert_eq!(s.root().visits, 0);
+        assert!(s.root().children.is_empty());
+
+        let r = s.search();
+        assert!(r.chosen_action.is_some());
+    }
+
+    #[test]
+    fn imported_stats_seed_root_children() {
+        let start = State::new();
+        let mut first = Searcher::new(&start, cfg(300), RandomEvaluator::new(32));
+        first.search();
+        let stats = first.export_stats();
+
+        let mut warm = Searcher::new(&start, cfg(300), RandomEvaluator::new(32));
+        warm.import_stats(&stats);
+        assert_eq!(
+            warm.root().visits,
+            first.root().children.iter().map(|c| c.visits).sum()
+        );
+        for st in &stats {
+            let c = warm
+                .root()
+                .children
+                .iter()
+                .find(|c| c.state == st.state)
+                .unwrap();
+            assert_eq!(c.visits, st.visits);
+            assert_eq!(c.value_sum, st.value_sum);
+        }
+
+        // foreign positions are skipped
+        let elsewhere = start
+            .child_after_move(0)
+            .unwrap()
+            .child_after_move(0)
+            .unwrap();
+        warm.import_stats(&[ChildStats {
+            state: elsewhere,
+            visits: 99,
+            value_sum: 1.0,
+        }]);
+        assert_eq!(warm.root().children.len(), stats.len());
+
+        let report = warm.search();
+        assert_eq!(report.root_visits, warm.root().visits);
+        assert!(report.root_visits >= 300 + stats.iter().map(|s| s.visits).sum::<u32>());
+    }
+
+    /// Two A moves on disjoint pits, each answered by B: playing them in
+    /// either order reaches the same positions.
+    fn transposing() -> State {
+        StateBuilder::new()
+            .pits(Player::A, [1, 1, 0, 1, 1, 0])
+            .pits(Player::B, [1, 1, 0, 1, 1, 0])
+            .build()
+            .unwrap()
+    }
+
+    fn collect<'a>(n: &'a Node, out: &mut HashMap<&'a State, Vec<&'a Node>>) {
+        out.entry(&n.state).or_default().push(n);
+        for c in &n.children {
+            collect(c, out);
+        }
+    }
+
+    #[test]
+    fn transpositions_share_statistics() {
+        let cfg = SearchConfig {
+            simulations: 2_000,
+            transpositions: true,
+            ..Default::default()
+        };
+        let mut s = Searcher::new(&transposing(), cfg, RandomEvaluator::new(32));
+        s.search();
+
+        let mut by_state = HashMap::new();
+        collect(s.root(), &mut by_state);
+        let merged: Vec<_> = by_state.values().filter(|nodes| nodes.len() > 1).collect();
+        assert!(!merged.is_empty(), "no transposition was explored");
+        for nodes in by_state.values() {
+            let own: u32 = nodes.iter().map(|n| n.visits).sum();
+            let (shared, _) = s.transpositions().get(&nodes[0].state).unwrap();
+            assert_eq!(shared, own);
+        }
+    }
+
+    #[test]
+    fn transpositions_off_keeps_table_empty() {
+        let mut s = Searcher::new(&transposing(), cfg(200), RandomEvaluator::new(32));
+        s.search();
+        assert!(s.transpositions().is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+//! Self-play game generation for training.
+
+use mancala::{Outcome, State};
+use rand::Rng;
+
+use super::evaluator::Evaluator;
+use super::mcts::{SearchConfig, mcts_search};
+use super::replay::Sample;
+use super::temperature::TemperatureSchedule;
+
+/// Play one game of `eval`-guided MCTS against itself from the opening.
+/// Move `i` is drawn from the root visit counts at `schedule.at(i)`.
+/// Returns one [`Sample`] per position played from: its visit
+/// distribution and the final result for its side to move.
+pub fn self_play_game<E: Evaluator>(
+    cfg: SearchConfig,
+    eval: &E,
+    schedule: TemperatureSchedule,
+    rng: &mut impl Rng,
+) -> Vec<Sample> {
+    let mut s = State::new();
+    let mut played = Vec::new();
+    while !s.is_terminal() {
+        let report = mcts_search(&s, cfg, eval);
+        let Some(a) = report.sample_action(schedule.at(played.len()), rng) else {
+            break;
+        };
+        let target = report.policy_target();
+        let policy = report
+            .child_visits
+            .iter()
+            .map(|&(m, _)| (m, target[m]))
+            .collect();
+        // safety: sampled moves come from the root's children
+        let next = s.child_after_move(a).unwrap();
+        played.push((s, policy));
+        s = next;
+    }
+
+    let outcome = s.outcome();
+    played
+        .into_iter()
+        .map(|(state, policy)| {
+            let value = match outcome {
+                Outcome::Win(p) if p == state.current_player() => 1.0,
+                Outcome::Win(_) => -1.0,
+                Outcome::Draw | Outcome::Ongoing => 0.0,
+            };
+            Sample {
+                state,
+                policy,
+                value,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn samples_replay_a_finished_game() {
+        let cfg = SearchConfig {
+            simulations: 40,
+            ..Default::default()
+        };
+        let schedule = TemperatureSchedule {
+            cutoff_move: 6,
+            ..Default::default()
+        };
+        let mut rng = StdRng::seed_from_u64(9);
+        let samples = self_play_game(cfg, &RandomEvaluator::new(16), schedule, &mut rng);
+        assert!(samples[0].state == State::new());
+
+        let first = &samples[0];
+        for pair in samples.windows(2) {
+            assert!(pair[1].state.is_successor_of(&pair[0].state).is_some());
+        }
+        for sample in &samples {
+            let total: f32 = sample.policy.iter().map(|&(_, p)| p).sum();
+            assert!((total - 1.0).abs() < 1e-5);
+            // one result, seen from each side to move
+            let same_side = sample.state.current_player() == first.state.current_player();
+            let expected = if same_side { first.value } else { -first.value };
+            assert_eq!(sample.value, expected);
+        }
+        assert!([-1.0, 0.0, 1.0].contains(&first.value));
+    }
+}
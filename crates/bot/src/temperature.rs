@@ -0,0 +1,62 @@
+/// Move-selection temperature over the course of a game.
+///
+/// `initial` applies to the first `cutoff_move` moves, `final_temp` after.
+/// [`self_play_game`](crate::self_play_game) samples each move at this
+/// temperature.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TemperatureSchedule {
+    pub initial: f32,
+    pub final_temp: f32,
+    pub cutoff_move: usize,
+}
+
+impl Default for TemperatureSchedule {
+    fn default() -> Self {
+        Self {
+            initial: 1.0,
+            final_temp: 0.0,
+            cutoff_move: 10,
+        }
+    }
+}
+
+impl TemperatureSchedule {
+    /// Temperature for the move with zero-based index `move_number`.
+    #[inline]
+    pub fn at(&self, move_number: usize) -> f32 {
+        if move_number < self.cutoff_move {
+            self.initial
+        } else {
+            self.final_temp
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_before_cutoff_final_after() {
+        let t = TemperatureSchedule {
+            initial: 1.0,
+            final_temp: 0.25,
+            cutoff_move: 4,
+        };
+        for m in 0..4 {
+            assert_eq!(t.at(m), 1.0);
+        }
+        for m in 4..20 {
+            assert_eq!(t.at(m), 0.25);
+        }
+    }
+
+    #[test]
+    fn zero_cutoff_is_always_final() {
+        let t = TemperatureSchedule {
+            cutoff_move: 0,
+            ..Default::default()
+        };
+        assert_eq!(t.at(0), t.final_temp);
+    }
+}
@@ -0,0 +1,101 @@
+//! Round-robin matches between evaluators, each driving its own MCTS.
+
+use mancala::{Outcome, Player, State};
+
+use super::evaluator::Evaluator;
+use super::mcts::{SearchConfig, mcts_search};
+
+/// Games between players `a` and `b` (indices into
+/// [`TournamentResult::players`]), counted from `a`'s side.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct PairRecord {
+    pub a: usize,
+    pub b: usize,
+    pub wins_a: u32,
+    pub draws: u32,
+    pub wins_b: u32,
+}
+
+impl PairRecord {
+    pub fn games(&self) -> u32 {
+        self.wins_a + self.draws + self.wins_b
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TournamentResult {
+    pub players: Vec<String>,
+    pub pairs: Vec<PairRecord>,
+}
+
+/// Play `games_per_pair` games between every two `entrants`, alternating
+/// who moves first, with each side searching with its own evaluator.
+pub fn round_robin(
+    entrants: &[(&str, &dyn Evaluator)],
+    games_per_pair: u32,
+    cfg: SearchConfig,
+) -> TournamentResult {
+    let mut result = TournamentResult {
+        players: entrants.iter().map(|(name, _)| name.to_string()).collect(),
+        pairs: Vec::new(),
+    };
+    for a in 0..entrants.len() {
+        for b in a + 1..entrants.len() {
+            let mut rec = PairRecord {
+                a,
+                b,
+                ..Default::default()
+            };
+            for g in 0..games_per_pair {
+                // `a` plays A in even games
+                let (first, second) = if g % 2 == 0 { (a, b) } else { (b, a) };
+                let winner = match play_game(entrants[first].1, entrants[second].1, cfg) {
+                    Outcome::Win(Player::A) => Some(first),
+                    Outcome::Win(Player::B) => Some(second),
+                    Outcome::Draw | Outcome::Ongoing => None,
+                };
+                match winner {
+                    Some(w) if w == a => rec.wins_a += 1,
+                    Some(_) => rec.wins_b += 1,
+                    None => rec.draws += 1,
+                }
+            }
+            result.pairs.push(rec);
+        }
+    }
+    result
+}
+
+/// One game from the opening, `first` playing A.
+fn play_game(first: &dyn Evaluator, second: &dyn Evaluator, cfg: SearchConfig) -> Outcome {
+    first.prepare();
+    second.prepare();
+    let mut s = State::new();
+    while let Some(side) = s.side_to_move() {
+        let eval = if side == Player::A { first } else { second };
+        let Some(next) = mcts_search(&s, cfg, &eval).chosen_state else {
+            break;
+        };
+        s = next;
+    }
+    s.outcome()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RandomEvaluator;
+
+    #[test]
+    fn round_robin_plays_every_pair() {
+        let e = RandomEvaluator::new(8);
+        let cfg = SearchConfig {
+            simulations: 8,
+            ..Default::default()
+        };
+        let r = round_robin(&[("x", &e), ("y", &e), ("z", &e)], 2, cfg);
+        assert_eq!(r.players, ["x", "y", "z"]);
+        assert_eq!(r.pairs.len(), 3);
+        assert!(r.pairs.iter().all(|p| p.games() == 2 && p.a < p.b));
+    }
+}
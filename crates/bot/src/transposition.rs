@@ -0,0 +1,38 @@
+//! Statistics shared by every node that holds the same position.
+
+use std::collections::HashMap;
+
+use mancala::State;
+
+/// Visit count and value sum per position, summed over every path that
+/// reached it. Values are from the position's side to move, as in
+/// [`Node`](crate::Node).
+#[derive(Clone, Debug, Default)]
+pub struct TranspositionTable {
+    stats: HashMap<State, (u32, f32)>,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stats.is_empty()
+    }
+
+    /// `(visits, value_sum)` for `state`, if it has been visited.
+    pub fn get(&self, state: &State) -> Option<(u32, f32)> {
+        self.stats.get(state).copied()
+    }
+
+    pub(crate) fn record(&mut self, state: &State, value: f32) {
+        let e = self.stats.entry(state.clone()).or_default();
+        e.0 += 1;
+        e.1 += value;
+    }
+}
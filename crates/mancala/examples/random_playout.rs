@@ -1,8 +1,20 @@
+//! Usage: `random_playout [SEED]`. Without a seed a random one is chosen and
+//! printed so the game can be replayed.
+
 use mancala::{Outcome, State};
-use rand::seq::IndexedRandom;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 fn main() {
-    let mut rng = rand::rng();
+    let seed = match std::env::args().nth(1) {
+        Some(arg) => arg.parse().unwrap_or_else(|_| {
+            eprintln!("seed must be an unsigned integer, got {arg:?}");
+            std::process::exit(2);
+        }),
+        None => rand::rng().random(),
+    };
+    println!("Seed: {seed}");
+    let mut rng = StdRng::seed_from_u64(seed);
 
     let mut s = State::new();
     println!("Initial:\n{}\n", s);
@@ -11,12 +23,9 @@ fn main() {
         match s.outcome() {
             Outcome::Ongoing => {
                 let who = s.current_player();
-                let moves = s.legal_moves();
-                if moves.is_empty() {
+                let Some((mv, child)) = s.apply_random_move(&mut rng) else {
                     break;
-                }
-                let &mv = moves.choose(&mut rng).unwrap();
-                let child = s.child_after_move(mv).unwrap();
+                };
 
                 println!("Step {step}: {} plays pit {mv}\n{}\n", who, child);
 
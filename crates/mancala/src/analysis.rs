@@ -0,0 +1,691 @@
+//! Exhaustive analysis over the game graph.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+use crate::{Outcome, PITS_PER_SIDE, Player, Rules, STATE_BYTES, State, TOTAL_CELLS};
+
+const MEMO_MAGIC: &[u8; 8] = b"MNCLMEM3";
+const COUNT_MAGIC: &[u8; 8] = b"MNCLCNT1";
+
+/// Exact values of solved positions, shared by [`solve`] and
+/// [`count_positions_with`] and persistable between runs.
+///
+/// A value is the final store difference for the side to move under
+/// perfect play from both sides.
+#[derive(Clone, Default)]
+pub struct MemoTable {
+    values: HashMap<State, i32>,
+    expansions: u64,
+}
+
+impl MemoTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Stored value for `state`, if solved.
+    pub fn get(&self, state: &State) -> Option<i32> {
+        self.values.get(state).copied()
+    }
+
+    /// Non-terminal positions [`solve`] has had to search through this table
+    /// (not counting table hits). Not saved.
+    pub fn expansions(&self) -> u64 {
+        self.expansions
+    }
+
+    /// Write every entry to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(MEMO_MAGIC)?;
+        w.write_all(&(self.values.len() as u64).to_le_bytes())?;
+        for (state, value) in &self.values {
+            w.write_all(&state.to_bytes())?;
+            w.write_all(&value.to_le_bytes())?;
+        }
+        w.flush()
+    }
+
+    /// Read a table written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut r = BufReader::new(File::open(path)?);
+        check_magic(&mut r, MEMO_MAGIC, "not a memo table")?;
+        let mut table = Self::new();
+        for _ in 0..read_u64(&mut r)? {
+            let state = read_state(&mut r)?;
+            let mut value = [0; 4];
+            r.read_exact(&mut value)?;
+            table.values.insert(state, i32::from_le_bytes(value));
+        }
+        Ok(table)
+    }
+}
+
+/// Perfect-play value of `state` for its side to move (final store
+/// difference), reusing and extending `memo`.
+///
+/// The game graph is acyclic, but its size limits this to small positions.
+pub fn solve(state: &State, memo: &mut MemoTable) -> i32 {
+    if let Some(v) = memo.get(state) {
+        return v;
+    }
+    let v = if state.is_terminal() || state.is_stalemate() {
+        state.score_for(state.current_player())
+    } else {
+        memo.expansions += 1;
+        let me = state.current_player();
+        state
+            .legal_actions()
+            .iter()
+            .map(|child| {
+                let v = solve(child, memo);
+                if child.current_player() == me { v } else { -v }
+            })
+            .max()
+            .expect("non-terminal positions have a move")
+    };
+    memo.values.insert(state.clone(), v);
+    v
+}
+
+/// Counts from a full enumeration of reachable positions.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct PositionStats {
+    /// Distinct positions reachable from the start (inclusive).
+    pub distinct_states: u64,
+    /// Distinct terminal positions among them, stalemates included.
+    pub terminal_states: u64,
+}
+
+/// Enumerate every position reachable from `start`, merging transpositions.
+///
+/// Memory grows with the number of distinct positions, so this is only
+/// practical for small stone totals.
+pub fn count_positions(start: &State) -> PositionStats {
+    count_positions_with(start, &mut MemoTable::new())
+}
+
+/// [`count_positions`] that also records every terminal position's value in
+/// `memo` for a later [`solve`]. The count itself always walks the whole
+/// graph: distinct positions do not add up across subtrees. Use
+/// [`PositionCount`] to split the walk over several runs.
+pub fn count_positions_with(start: &State, memo: &mut MemoTable) -> PositionStats {
+    let mut count = PositionCount::new(start);
+    count.step(u64::MAX, memo);
+    count.stats()
+}
+
+/// A [`count_positions`] walk in progress, which can be advanced in steps
+/// and saved to disk between them.
+///
+/// The checkpoint holds every position seen so far, so it is as large as
+/// the walk's own memory.
+#[derive(Clone, Debug)]
+pub struct PositionCount {
+    seen: HashSet<State>,
+    stack: Vec<State>,
+    stats: PositionStats,
+}
+
+impl PositionCount {
+    /// A walk from `start` with nothing visited yet.
+    pub fn new(start: &State) -> Self {
+        Self {
+            seen: HashSet::from([start.clone()]),
+            stack: vec![start.clone()],
+            stats: PositionStats::default(),
+        }
+    }
+
+    /// Visit up to `budget` more positions, recording terminal values in
+    /// `memo`. Returns whether the walk is finished.
+    pub fn step(&mut self, budget: u64, memo: &mut MemoTable) -> bool {
+        for _ in 0..budget {
+            let Some(s) = self.stack.pop() else {
+                break;
+            };
+            self.stats.distinct_states += 1;
+            if s.is_terminal() || s.is_stalemate() {
+                self.stats.terminal_states += 1;
+                solve(&s, memo);
+                continue;
+            }
+            for child in s.legal_actions() {
+                if self.seen.insert(child.clone()) {
+                    self.stack.push(child);
+                }
+            }
+        }
+        self.is_done()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.stack.is_empty()
+    }
+
+    /// Counts so far; final once [`is_done`](Self::is_done).
+    pub fn stats(&self) -> PositionStats {
+        self.stats
+    }
+
+    /// Write the walk's state to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        w.write_all(COUNT_MAGIC)?;
+        w.write_all(&self.stats.distinct_states.to_le_bytes())?;
+        w.write_all(&self.stats.terminal_states.to_le_bytes())?;
+        write_states(&mut w, self.stack.iter())?;
+        write_states(&mut w, self.seen.iter())?;
+        w.flush()
+    }
+
+    /// Read a walk written by [`save`](Self::save).
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut r = BufReader::new(File::open(path)?);
+        check_magic(&mut r, COUNT_MAGIC, "not a position count")?;
+        let stats = PositionStats {
+            distinct_states: read_u64(&mut r)?,
+            terminal_states: read_u64(&mut r)?,
+        };
+        let stack = (0..read_u64(&mut r)?)
+            .map(|_| read_state(&mut r))
+            .collect::<io::Result<_>>()?;
+        let seen = (0..read_u64(&mut r)?)
+            .map(|_| read_state(&mut r))
+            .collect::<io::Result<_>>()?;
+        Ok(Self { seen, stack, stats })
+    }
+}
+
+fn check_magic(r: &mut impl Read, magic: &[u8; 8], what: &str) -> io::Result<()> {
+    let mut got = [0; 8];
+    r.read_exact(&mut got)?;
+    if &got != magic {
+        return Err(io::Error::new(ErrorKind::InvalidData, what));
+    }
+    Ok(())
+}
+
+fn write_states<'a>(
+    w: &mut impl Write,
+    states: impl ExactSizeIterator<Item = &'a State>,
+) -> io::Result<()> {
+    w.write_all(&(states.len() as u64).to_le_bytes())?;
+    for state in states {
+        w.write_all(&state.to_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_u64(r: &mut impl Read) -> io::Result<u64> {
+    let mut n = [0; 8];
+    r.read_exact(&mut n)?;
+    Ok(u64::from_le_bytes(n))
+}
+
+fn read_state(r: &mut impl Read) -> io::Result<State> {
+    let mut blob = [0; STATE_BYTES];
+    r.read_exact(&mut blob)?;
+    State::from_bytes(&blob).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// One position per mirror class in `states`, in order of first
+/// appearance. The kept representative is the one whose encoding is the
+/// [`canonical_key`](State::canonical_key), whichever member appeared.
+pub fn dedup_by_symmetry(states: &[State]) -> Vec<State> {
+    let mut seen = HashSet::new();
+    states
+        .iter()
+        .filter(|s| seen.insert(s.canonical_key()))
+        .map(|s| {
+            let m = s.mirror();
+            if m.to_bytes() < s.to_bytes() {
+                m
+            } else {
+                s.clone()
+            }
+        })
+        .collect()
+}
+
+/// Every way to place exactly `total` stones over the pits and stores
+/// (standard rules, `to_move` to play), one position per distribution.
+///
+/// Some distributions never arise in play, such as one side empty while the
+/// other still holds stones; they are included so the count is exactly
+/// `C(total + 2P + 1, 2P + 1)`. Panics if `total` exceeds `u8::MAX`.
+pub fn enumerate_positions(total: u16, to_move: Player) -> impl Iterator<Item = State> {
+    const SLOTS: usize = TOTAL_CELLS;
+    let total = u8::try_from(total).expect("total must fit in one pit");
+    // counts for every slot but the last, which takes the remainder
+    let mut free = [0u8; SLOTS - 1];
+    let mut used = 0u8;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let mut slots = [0u8; SLOTS];
+        slots[..SLOTS - 1].copy_from_slice(&free);
+        slots[SLOTS - 1] = total - used;
+
+        // odometer step over the free slots, keeping their sum <= total
+        done = true;
+        for i in (0..SLOTS - 1).rev() {
+            if used < total {
+                free[i] += 1;
+                used += 1;
+                done = false;
+                break;
+            }
+            used -= free[i];
+            free[i] = 0;
+        }
+
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[0].copy_from_slice(&slots[..PITS_PER_SIDE]);
+        pits[1].copy_from_slice(&slots[PITS_PER_SIDE..2 * PITS_PER_SIDE]);
+        let stores = [slots[2 * PITS_PER_SIDE], slots[2 * PITS_PER_SIDE + 1]];
+        Some(State::from_parts(pits, stores, to_move))
+    })
+}
+
+/// Perfect-play results for every standard-rules position with at most
+/// `max_stones` stones left in the pits, whatever the stores hold.
+///
+/// Play from a position never depends on the stores, so an entry is keyed
+/// by the pits and side to move alone and holds the net stones the mover
+/// goes on to gain. Entries are filled backward from the end: a layer of
+/// `k` pit stones only needs smaller layers and, within the layer, moves
+/// that shift stones toward the mover's store without reaching it, which
+/// are therefore processed first.
+#[derive(Clone, Debug)]
+pub struct Tablebase {
+    max_stones: u16,
+    gains: HashMap<State, i8>,
+}
+
+impl Tablebase {
+    /// Build every layer up to `max_stones` (at most 127). Size grows
+    /// quickly, so keep this to endgames of a dozen stones or so.
+    pub fn build(max_stones: u16) -> Self {
+        assert!(max_stones <= i8::MAX as u16, "gains are stored as i8");
+        let mut gains = HashMap::new();
+        for k in 0..=max_stones {
+            let mut layer: Vec<State> = Player::both()
+                .flat_map(|p| enumerate_positions(k, p))
+                .filter(|s| s.stores() == (0, 0) && !s.is_terminal())
+                .collect();
+            layer.sort_by_key(|s| std::cmp::Reverse(advancement(s)));
+            for s in layer {
+                let g = best_gain(&s, &gains);
+                gains.insert(s, g);
+            }
+        }
+        Self { max_stones, gains }
+    }
+
+    pub fn max_stones(&self) -> u16 {
+        self.max_stones
+    }
+
+    /// Non-terminal positions stored.
+    pub fn len(&self) -> usize {
+        self.gains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gains.is_empty()
+    }
+
+    /// Final store difference for the side to move under perfect play, like
+    /// [`solve`]. `None` outside the table: variant rules or too many
+    /// stones in the pits.
+    pub fn value(&self, state: &State) -> Option<i32> {
+        if state.rules() != Rules::default() || pit_stones(state) > self.max_stones {
+            return None;
+        }
+        let me = state.current_player();
+        let now = state.score_for(me);
+        if state.is_terminal() {
+            return Some(now);
+        }
+        self.gains.get(&pits_only(state)).map(|&g| now + g as i32)
+    }
+
+    /// Game-theoretic outcome of `state`, if it is in the table.
+    pub fn probe(&self, state: &State) -> Option<Outcome> {
+        Some(outcome_of(state, self.value(state)?))
+    }
+}
+
+/// Outcome once `state`'s side to move ends `value` stones ahead.
+fn outcome_of(state: &State, value: i32) -> Outcome {
+    let me = state.current_player();
+    match value {
+        v if v > 0 => Outcome::Win(me),
+        v if v < 0 => Outcome::Win(me.opponent()),
+        _ => Outcome::Draw,
+    }
+}
+
+impl State {
+    /// Result under perfect play from both sides, via [`solve`] with a
+    /// fresh [`MemoTable`]; unlike [`outcome`](Self::outcome) this is never
+    /// `Ongoing`. Only practical for small stone totals; share a table
+    /// through `solve` when asking about many positions.
+    pub fn theoretical_result(&self) -> Outcome {
+        outcome_of(self, solve(self, &mut MemoTable::new()))
+    }
+}
+
+fn pit_stones(s: &State) -> u16 {
+    s.total_stones() - s.store(Player::A) as u16 - s.store(Player::B) as u16
+}
+
+/// `s` with both stores emptied: the key for its [`Tablebase`] entry.
+fn pits_only(s: &State) -> State {
+    State::from_parts(
+        [*s.pits(Player::A), *s.pits(Player::B)],
+        [0, 0],
+        s.current_player(),
+    )
+}
+
+/// Sum of pit index times stones over both sides. Every move that keeps all
+/// stones in the pits raises it, so higher values are solved first.
+fn advancement(s: &State) -> u32 {
+    Player::both()
+        .flat_map(|p| s.pits(p).iter().enumerate())
+        .map(|(i, &n)| i as u32 * n as u32)
+        .sum()
+}
+
+/// Net future gain for the mover of the stores-empty position `s`, from
+/// already-solved successors.
+fn best_gain(s: &State, gains: &HashMap<State, i8>) -> i8 {
+    let me = s.current_player();
+    s.legal_transitions()
+        .into_iter()
+        .map(|(_, child)| {
+            let now = child.score_for(me);
+            let rest = if child.is_terminal() {
+                0
+            } else {
+                gains[&pits_only(&child)] as i32
+            };
+            if child.current_player() == me {
+                now + rest
+            } else {
+                now - rest
+            }
+        })
+        .max()
+        .expect("non-terminal positions have a move") as i8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GrandSlam, StateBuilder};
+
+    #[test]
+    fn dedup_by_symmetry_collapses_mirror_pairs() {
+        let start = State::new();
+        let x = start.child_after_move(0).unwrap();
+        let y = start.child_after_move(3).unwrap();
+        let out = dedup_by_symmetry(&[x.clone(), x.mirror(), y.clone(), x.clone()]);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].canonical_key(), x.canonical_key());
+        assert_eq!(out[0].to_bytes(), x.canonical_key());
+        assert_eq!(out[1].canonical_key(), y.canonical_key());
+
+        // unrelated positions all survive
+        let distinct: Vec<_> = start
+            .legal_transitions()
+            .into_iter()
+            .map(|(_, c)| c)
+            .collect();
+        assert_eq!(dedup_by_symmetry(&distinct).len(), distinct.len());
+    }
+
+    #[test]
+    fn terminal_start_counts_itself() {
+        let s = StateBuilder::new().store(Player::A, 3).build().unwrap();
+        let stats = count_positions(&s);
+        assert_eq!(stats.distinct_states, 1);
+        assert_eq!(stats.terminal_states, 1);
+    }
+
+    #[test]
+    fn tiny_position_counts_with_transposition() {
+        // A: [0 0 0 0 1 1], B: [0 0 0 0 0 1], A to move.
+        //   A5 -> extra turn -> A4 -> B5 -> sweep: A2 B1, B to move
+        //   A4            -> B5 -> sweep: A2 B1, B to move (same position)
+        // start, after A5, after A5 A4, after A4, terminal = 5 distinct.
+        let s = StateBuilder::new()
+            .pit(Player::A, PITS_PER_SIDE - 2, 1)
+            .pit(Player::A, PITS_PER_SIDE - 1, 1)
+            .pit(Player::B, PITS_PER_SIDE - 1, 1)
+            .build()
+            .unwrap();
+        let stats = count_positions(&s);
+        assert_eq!(stats.distinct_states, 5);
+        assert_eq!(stats.terminal_states, 1);
+    }
+
+    /// Endgame with a few choices on both sides.
+    fn small_endgame() -> State {
+        StateBuilder::new()
+            .pits(Player::A, [0, 1, 0, 1, 0, 1])
+            .pits(Player::B, [1, 0, 1, 0, 0, 1])
+            .store(Player::A, 10)
+            .store(Player::B, 9)
+            .build()
+            .unwrap()
+    }
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("{name}-{}.memo", std::process::id()))
+    }
+
+    #[test]
+    fn loaded_partial_table_is_reused_by_solve() {
+        let root = small_endgame();
+        let mut fresh = MemoTable::new();
+        let expected = solve(&root, &mut fresh);
+
+        // solve one subtree, checkpoint it, resume from disk
+        let mut partial = MemoTable::new();
+        solve(&root.child_after_move(1).unwrap(), &mut partial);
+        let path = temp_path("partial");
+        partial.save(&path).unwrap();
+        let mut resumed = MemoTable::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(resumed.len(), partial.len());
+        assert_eq!(resumed.expansions(), 0);
+
+        assert_eq!(solve(&root, &mut resumed), expected);
+        assert!(resumed.expansions() < fresh.expansions());
+        assert_eq!(
+            resumed.expansions() + partial.expansions(),
+            fresh.expansions()
+        );
+    }
+
+    #[test]
+    fn counter_fills_terminal_values() {
+        let root = small_endgame();
+        let mut memo = MemoTable::new();
+        let stats = count_positions_with(&root, &mut memo);
+        assert_eq!(stats, count_positions(&root));
+        assert_eq!(memo.len() as u64, stats.terminal_states);
+        assert_eq!(memo.expansions(), 0);
+        let v = solve(&root, &mut memo);
+        assert_eq!(v, solve(&root, &mut MemoTable::new()));
+    }
+
+    #[test]
+    fn position_count_resumes_from_a_checkpoint() {
+        let root = small_endgame();
+        let full = count_positions(&root);
+        let mut memo = MemoTable::new();
+
+        let mut count = PositionCount::new(&root);
+        assert!(!count.step(4, &mut memo));
+        assert_eq!(count.stats().distinct_states, 4);
+        let path = temp_path("count");
+        count.save(&path).unwrap();
+        let mut resumed = PositionCount::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(resumed.stats(), count.stats());
+
+        while !resumed.step(3, &mut memo) {}
+        assert_eq!(resumed.stats(), full);
+        assert_eq!(memo.len() as u64, full.terminal_states);
+    }
+
+    #[test]
+    fn load_rejects_foreign_files() {
+        let path = temp_path("foreign");
+        std::fs::write(&path, b"not a table").unwrap();
+        let err = MemoTable::load(&path).err().unwrap();
+        let count_err = PositionCount::load(&path).err().unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(count_err.kind(), ErrorKind::InvalidData);
+    }
+
+    fn binomial(n: u64, k: u64) -> u64 {
+        (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+    }
+
+    #[test]
+    fn enumeration_matches_stars_and_bars() {
+        let slots = 2 * PITS_PER_SIDE as u64 + 2;
+        for total in 0..=3u16 {
+            let all: Vec<State> = enumerate_positions(total, Player::B).collect();
+            assert_eq!(
+                all.len() as u64,
+                binomial(total as u64 + slots - 1, slots - 1),
+                "total {total}"
+            );
+            assert!(all.iter().all(|s| s.total_stones() == total));
+            assert!(all.iter().all(|s| s.current_player() == Player::B));
+            let distinct: HashSet<&State> = all.iter().collect();
+            assert_eq!(distinct.len(), all.len());
+        }
+    }
+
+    #[test]
+    fn tablebase_agrees_with_minimax() {
+        let tb = Tablebase::build(2);
+        let mut memo = MemoTable::new();
+        for total in 0..=4 {
+            for s in Player::both().flat_map(|p| enumerate_positions(total, p)) {
+                if pit_stones(&s) > 2 {
+                    assert_eq!(tb.probe(&s), None);
+                    continue;
+                }
+                let v = solve(&s, &mut memo);
+                assert_eq!(tb.value(&s), Some(v), "{s:?}");
+                assert_eq!(tb.probe(&s), Some(outcome_of(&s, v)));
+            }
+        }
+    }
+
+    #[test]
+    fn tablebase_answers_late_game_positions() {
+        let tb = Tablebase::build(4);
+        let s = small_endgame();
+        assert!(pit_stones(&s) > 4);
+        assert_eq!(tb.probe(&s), None);
+
+        let late = StateBuilder::new()
+            .pit(Player::A, 4, 1)
+            .pit(Player::A, 5, 1)
+            .pit(Player::B, 2, 2)
+            .store(Player::A, 20)
+            .store(Player::B, 24)
+            .build()
+            .unwrap();
+        assert_eq!(tb.value(&late), Some(solve(&late, &mut MemoTable::new())));
+    }
+
+    #[test]
+    fn stalemate_is_solved_on_the_stores() {
+        // A's only move would take all of B's stones, which is forbidden
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 4, 3)
+            .store(Player::A, 22)
+            .store(Player::B, 22)
+            .rules(Rules {
+                grand_slam: GrandSlam::Forbidden,
+                ..Rules::default()
+            })
+            .build()
+            .unwrap();
+        assert!(s.is_stalemate());
+        assert_eq!(solve(&s, &mut MemoTable::new()), 0);
+        assert_eq!(s.theoretical_result(), Outcome::Draw);
+        assert_eq!(s.theoretical_result(), s.outcome());
+    }
+
+    #[test]
+    fn counter_records_stalemates() {
+        let forbidden = Rules {
+            grand_slam: GrandSlam::Forbidden,
+            ..Rules::default()
+        };
+        // B's pit 3 drops onto pit 4, leaving A only a forbidden grand slam
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 3, 1)
+            .pit(Player::B, 4, 2)
+            .store(Player::A, 22)
+            .store(Player::B, 21)
+            .to_move(Player::B)
+            .rules(forbidden)
+            .build()
+            .unwrap();
+        let stalemate = s.child_after_move(3).unwrap();
+        assert!(stalemate.is_stalemate());
+
+        let mut memo = MemoTable::new();
+        let stats = count_positions_with(&s, &mut memo);
+        assert_eq!(memo.len() as u64, stats.terminal_states);
+        assert_eq!(memo.get(&stalemate), Some(1));
+        assert_eq!(memo.expansions(), 0);
+    }
+
+    #[test]
+    fn theoretical_result_of_a_won_endgame() {
+        // A takes pit 5 into the store, then walks pit 4 home after B's only
+        // reply: 2-1 whatever B does.
+        let s = StateBuilder::new()
+            .pit(Player::A, 4, 1)
+            .pit(Player::A, 5, 1)
+            .pit(Player::B, 0, 1)
+            .build()
+            .unwrap();
+        assert_eq!(s.outcome(), Outcome::Ongoing);
+        assert_eq!(s.theoretical_result(), Outcome::Win(Player::A));
+        assert_eq!(s.mirror().theoretical_result(), Outcome::Win(Player::B));
+
+        let over = StateBuilder::new().store(Player::B, 3).build().unwrap();
+        assert_eq!(over.theoretical_result(), over.outcome());
+    }
+}
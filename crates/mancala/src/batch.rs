@@ -0,0 +1,49 @@
+//! Move generation over slices of positions.
+
+use crate::State;
+
+/// [`State::legal_moves_mask`] for each position.
+pub fn legal_masks(states: &[State]) -> Vec<u16> {
+    states.iter().map(State::legal_moves_mask).collect()
+}
+
+/// [`State::legal_transitions`] for each position.
+pub fn successors(states: &[State]) -> Vec<Vec<(usize, State)>> {
+    states.iter().map(State::legal_transitions).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PITS_PER_SIDE, Player, StateBuilder};
+
+    #[test]
+    fn batch_matches_single_state_calls() {
+        let opening = State::new();
+        let states = vec![
+            opening.clone(),
+            opening.child_after_move(2).unwrap(),
+            opening.child_after_move(5).unwrap(),
+            StateBuilder::new()
+                .pit(Player::A, 1, 2)
+                .pit(Player::B, 4, 1)
+                .build()
+                .unwrap(),
+            StateBuilder::new().store(Player::B, 3).build().unwrap(),
+        ];
+
+        let masks = legal_masks(&states);
+        let succ = successors(&states);
+        assert_eq!(masks.len(), states.len());
+        assert_eq!(succ.len(), states.len());
+        for ((s, &mask), next) in states.iter().zip(&masks).zip(&succ) {
+            assert_eq!(mask, s.legal_moves_mask());
+            let from_mask: Vec<usize> = (0..PITS_PER_SIDE).filter(|i| mask >> i & 1 == 1).collect();
+            assert_eq!(from_mask, s.legal_moves());
+            assert_eq!(next, &s.legal_transitions());
+        }
+        assert_eq!(masks[0], (1 << PITS_PER_SIDE) - 1);
+        assert_eq!(masks[3], 1 << 1);
+        assert_eq!(masks[4], 0);
+    }
+}
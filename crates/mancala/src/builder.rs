@@ -0,0 +1,157 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{InvalidState, PITS_PER_SIDE, Player, Rules, State};
+
+/// Why a [`StateBuilder`] could not produce a position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BuildError {
+    /// A pit index was not below `PITS_PER_SIDE`.
+    PitOutOfBounds(usize),
+    /// The assembled position failed [`State::validate`].
+    Invalid(InvalidState),
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::PitOutOfBounds(i) => write!(f, "pit index {i} out of bounds"),
+            BuildError::Invalid(e) => write!(f, "invalid position: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Fluent construction of arbitrary positions.
+///
+/// Starts from an empty board with `Player::A` to move under default rules.
+#[derive(Clone, Debug, Default)]
+pub struct StateBuilder {
+    pits: [[u8; PITS_PER_SIDE]; 2],
+    stores: [u8; 2],
+    to_move: Option<Player>,
+    rules: Rules,
+    bad_pit: Option<usize>,
+}
+
+impl StateBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the stones in one small pit.
+    pub fn pit(mut self, side: Player, idx: usize, stones: u8) -> Self {
+        match self.pits[side.idx()].get_mut(idx) {
+            Some(p) => *p = stones,
+            None => self.bad_pit = self.bad_pit.or(Some(idx)),
+        }
+        self
+    }
+
+    /// Set all small pits of a side at once.
+    pub fn pits(mut self, side: Player, pits: [u8; PITS_PER_SIDE]) -> Self {
+        self.pits[side.idx()] = pits;
+        self
+    }
+
+    /// Set the stones in a side's store.
+    pub fn store(mut self, side: Player, stones: u8) -> Self {
+        self.stores[side.idx()] = stones;
+        self
+    }
+
+    pub fn to_move(mut self, player: Player) -> Self {
+        self.to_move = Some(player);
+        self
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Validate and produce the position.
+    pub fn build(self) -> Result<State, BuildError> {
+        if let Some(i) = self.bad_pit {
+            return Err(BuildError::PitOutOfBounds(i));
+        }
+        let mut s = State::from_parts(self.pits, self.stores, self.to_move.unwrap_or(Player::A));
+        s.set_rules(self.rules);
+        s.validate().map_err(BuildError::Invalid)?;
+        Ok(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_capture_fixture() {
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, PITS_PER_SIDE - 1 - 1, 3)
+            .to_move(Player::A)
+            .build()
+            .unwrap();
+        let child = s.child_after_move(0).unwrap();
+        assert_eq!(child.store(Player::A), 4);
+        assert_eq!(child.pits(Player::A)[1], 0);
+        assert_eq!(child.pits(Player::B)[PITS_PER_SIDE - 1 - 1], 0);
+    }
+
+    #[test]
+    fn default_matches_empty_board() {
+        let s = StateBuilder::new().build().unwrap();
+        assert!(s.is_terminal());
+        assert_eq!(s.current_player(), Player::A);
+        assert_eq!(s.rules(), Rules::default());
+    }
+
+    #[test]
+    fn rejects_out_of_bounds_pit() {
+        let r = StateBuilder::new().pit(Player::B, PITS_PER_SIDE, 1).build();
+        assert_eq!(r.err(), Some(BuildError::PitOutOfBounds(PITS_PER_SIDE)));
+    }
+
+    #[test]
+    fn rejects_overfull_board() {
+        let r = StateBuilder::new()
+            .store(Player::A, 200)
+            .store(Player::B, 100)
+            .build();
+        assert_eq!(
+            r.err(),
+            Some(BuildError::Invalid(InvalidState::TooManyStones(300)))
+        );
+    }
+
+    #[test]
+    fn rejects_unswept_terminal() {
+        let r = StateBuilder::new().pit(Player::B, 0, 2).build();
+        assert_eq!(
+            r.err(),
+            Some(BuildError::Invalid(InvalidState::UnsweptTerminal))
+        );
+    }
+
+    #[test]
+    fn builder_sets_mover_and_rules() {
+        let rules = Rules {
+            extra_turn_bonus: 1,
+            ..Rules::default()
+        };
+        let s = StateBuilder::new()
+            .pits(Player::A, [1; PITS_PER_SIDE])
+            .pits(Player::B, [2; PITS_PER_SIDE])
+            .store(Player::B, 5)
+            .to_move(Player::B)
+            .rules(rules)
+            .build()
+            .unwrap();
+        assert_eq!(s.current_player(), Player::B);
+        assert_eq!(s.rules(), rules);
+        assert_eq!(s.store(Player::B), 5);
+        assert_eq!(s.pits(Player::B), &[2; PITS_PER_SIDE]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Fixed-size binary encoding of positions.
+//!
+//! Layout: A's pits, B's pits (index order), A's store, B's store, side to
+//! move (`0` = A, `1` = B), then the rule fields: extra-turn bonus,
+//! feeding and capture-grants-extra-turn (`0`/`1` each), grand slam (`0`
+//! allowed, `1` forbidden, `2` no capture).
+//!
+//! [`State::id`] writes the same bytes as unpadded RFC 4648 base32.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{BuildError, GrandSlam, PITS_PER_SIDE, Player, Rules, State, StateBuilder};
+
+/// Length of [`State::to_bytes`] output.
+pub const STATE_BYTES: usize = 2 * PITS_PER_SIDE + 7;
+
+/// Alphabet of [`State::id`]: RFC 4648 base32.
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Why bytes could not be read as a position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum DecodeError {
+    /// Input was not exactly [`STATE_BYTES`] long.
+    Length(usize),
+    /// The side-to-move byte was neither 0 nor 1.
+    BadSide(u8),
+    /// A rule byte was out of range for its field.
+    BadRule(u8),
+    /// The bytes describe a position the engine rejects.
+    Invalid(BuildError),
+    /// An [`id`](State::id) held a character outside the base32 alphabet,
+    /// or nonzero padding bits.
+    BadChar(char),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Length(n) => write!(f, "expected {STATE_BYTES} bytes, got {n}"),
+            DecodeError::BadSide(b) => write!(f, "bad side-to-move byte {b}"),
+            DecodeError::BadRule(b) => write!(f, "bad rule byte {b}"),
+            DecodeError::Invalid(e) => write!(f, "{e}"),
+            DecodeError::BadChar(c) => write!(f, "bad id character {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl State {
+    /// Compact encoding, rules included (see the module docs).
+    pub fn to_bytes(&self) -> [u8; STATE_BYTES] {
+        let mut out = [0; STATE_BYTES];
+        out[..PITS_PER_SIDE].copy_from_slice(self.pits(Player::A));
+        out[PITS_PER_SIDE..2 * PITS_PER_SIDE].copy_from_slice(self.pits(Player::B));
+        let tail = &mut out[2 * PITS_PER_SIDE..];
+        tail[0] = self.store(Player::A);
+        tail[1] = self.store(Player::B);
+        tail[2] = match self.current_player() {
+            Player::A => 0,
+            Player::B => 1,
+        };
+        tail[3] = self.rules().extra_turn_bonus;
+        tail[4] = self.rules().feeding_required as u8;
+        tail[5] = self.rules().capture_grants_extra_turn as u8;
+        tail[6] = match self.rules().grand_slam {
+            GrandSlam::Allowed => 0,
+            GrandSlam::Forbidden => 1,
+            GrandSlam::NoCapture => 2,
+        };
+        out
+    }
+
+    /// The smaller of the encodings of this position and its
+    /// [`mirror`](Self::mirror), equal for both members of a mirror pair.
+    pub fn canonical_key(&self) -> [u8; STATE_BYTES] {
+        self.to_bytes().min(self.mirror().to_bytes())
+    }
+
+    /// Inverse of [`to_bytes`](Self::to_bytes). The position must pass
+    /// [`validate`](Self::validate).
+    pub fn from_bytes(bytes: &[u8]) -> Result<State, DecodeError> {
+        if bytes.len() != STATE_BYTES {
+            return Err(DecodeError::Length(bytes.len()));
+        }
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[0].copy_from_slice(&bytes[..PITS_PER_SIDE]);
+        pits[1].copy_from_slice(&bytes[PITS_PER_SIDE..2 * PITS_PER_SIDE]);
+        let tail = &bytes[2 * PITS_PER_SIDE..];
+        let to_move = match tail[2] {
+            0 => Player::A,
+            1 => Player::B,
+            b => return Err(DecodeError::BadSide(b)),
+        };
+        StateBuilder::new()
+            .pits(Player::A, pits[0])
+            .pits(Player::B, pits[1])
+            .store(Player::A, tail[0])
+            .store(Player::B, tail[1])
+            .to_move(to_move)
+            .rules(Rules {
+                extra_turn_bonus: tail[3],
+                feeding_required: flag(tail[4])?,
+                capture_grants_extra_turn: flag(tail[5])?,
+                grand_slam: match tail[6] {
+                    0 => GrandSlam::Allowed,
+                    1 => GrandSlam::Forbidden,
+                    2 => GrandSlam::NoCapture,
+                    b => return Err(DecodeError::BadRule(b)),
+                },
+            })
+            .build()
+            .map_err(DecodeError::Invalid)
+    }
+
+    /// Short, lossless position ID for logs and bug reports: the
+    /// [`to_bytes`](Self::to_bytes) encoding in base32, rules included.
+    pub fn id(&self) -> String {
+        let mut out = String::new();
+        let (mut acc, mut bits) = (0u32, 0);
+        for b in self.to_bytes() {
+            acc = (acc << 8 | b as u32) & 0xfff;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(BASE32[(acc >> bits & 31) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            out.push(BASE32[(acc << (5 - bits) & 31) as usize] as char);
+        }
+        out
+    }
+
+    /// Inverse of [`id`](Self::id); lowercase letters are accepted.
+    pub fn from_id(id: &str) -> Result<State, DecodeError> {
+        let mut bytes = Vec::with_capacity(STATE_BYTES);
+        let (mut acc, mut bits) = (0u32, 0);
+        for c in id.chars() {
+            let up = c.to_ascii_uppercase();
+            let v = BASE32
+                .iter()
+                .position(|&a| a as char == up)
+                .ok_or(DecodeError::BadChar(c))?;
+            acc = (acc << 5 | v as u32) & 0xfff;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                bytes.push((acc >> bits) as u8);
+            }
+        }
+        if acc & ((1 << bits) - 1) != 0 {
+            // a canonical id pads its last character with zeros
+            return Err(DecodeError::BadChar(id.chars().last().unwrap()));
+        }
+        State::from_bytes(&bytes)
+    }
+}
+
+fn flag(b: u8) -> Result<bool, DecodeError> {
+    match b {
+        0 => Ok(false),
+        1 => Ok(true),
+        b => Err(DecodeError::BadRule(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_positions_and_rules() {
+        let mut s = State::with_rules(Rules {
+            extra_turn_bonus: 2,
+            feeding_required: true,
+            capture_grants_extra_turn: true,
+            grand_slam: GrandSlam::NoCapture,
+        });
+        for m in [2, 5, 1, 0] {
+            let back = State::from_bytes(&s.to_bytes()).unwrap();
+            assert!(back == s);
+            assert_eq!(back.rules(), s.rules());
+            s = s.child_after_move(m).unwrap();
+        }
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        let mut s = State::with_rules(Rules {
+            feeding_required: true,
+            ..Rules::default()
+        });
+        for m in [2, 5, 1, 0] {
+            let id = s.id();
+            assert!(id.len() <= (8 * STATE_BYTES).div_ceil(5));
+            let back = State::from_id(&id).unwrap();
+            assert!(back == s);
+            assert_eq!(State::from_id(&id.to_lowercase()).unwrap(), s);
+            s = s.child_after_move(m).unwrap();
+        }
+        assert_ne!(State::new().id(), State::new_with_mover(Player::B).id());
+        assert_eq!(State::from_id("AB!"), Err(DecodeError::BadChar('!')));
+        assert!(matches!(State::from_id("AA"), Err(DecodeError::Length(1))));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let good = State::new().to_bytes();
+        assert_eq!(
+            State::from_bytes(&good[1..]).err(),
+            Some(DecodeError::Length(STATE_BYTES - 1))
+        );
+        let mut side = good;
+        side[2 * PITS_PER_SIDE + 2] = 7;
+        assert_eq!(
+            State::from_bytes(&side).err(),
+            Some(DecodeError::BadSide(7))
+        );
+        let mut full = good;
+        full[2 * PITS_PER_SIDE] = 250;
+        assert!(matches!(
+            State::from_bytes(&full),
+            Err(DecodeError::Invalid(_))
+        ));
+    }
+}
@@ -3,3 +3,29 @@ pub const PITS_PER_SIDE: usize = 6;
 
 /// Initial stones in each small pit (standard Mancala is 4).
 pub const STONES_PER_PIT: u8 = 4;
+
+/// Most legal moves a position can have: one per pit on the mover's side.
+pub const MAX_ACTIONS: usize = PITS_PER_SIDE;
+
+/// Cells on the board: every small pit plus both stores.
+pub const TOTAL_CELLS: usize = 2 * PITS_PER_SIDE + 2;
+
+/// Stones on a fresh board. Variants with an extra-turn bonus add more
+/// during play.
+pub const INITIAL_TOTAL_STONES: u16 = 2 * PITS_PER_SIDE as u16 * STONES_PER_PIT as u16;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::State;
+
+    #[test]
+    fn derived_constants_match_the_standard_board() {
+        assert_eq!(MAX_ACTIONS, 6);
+        assert_eq!(TOTAL_CELLS, 14);
+        assert_eq!(INITIAL_TOTAL_STONES, 48);
+        assert_eq!(State::new().total_stones(), INITIAL_TOTAL_STONES);
+        assert_eq!(State::new().legal_moves().len(), MAX_ACTIONS);
+        assert_eq!(State::RING_LEN, TOTAL_CELLS);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{PITS_PER_SIDE, Player, State};
-use std::fmt::{self, Display, Formatter};
+use std::ffi::OsStr;
+use std::fmt::{self, Display, Formatter, Write};
 
 const RESET: &str = "\x1b[0m";
 const BOLD: &str = "\x1b[1m";
@@ -7,67 +8,171 @@ const DIM: &str = "\x1b[2m";
 const CYAN: &str = "\x1b[36m"; // Player::A
 const MAGENTA: &str = "\x1b[35m"; // Player::B
 
+/// Colors and emphasis for rendering a [`State`].
+///
+/// Colors are raw ANSI escape sequences (e.g. `"\x1b[32m"`); an empty string
+/// leaves that text uncolored.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct DisplayStyle {
+    pub color_a: &'static str,
+    pub color_b: &'static str,
+    /// Bold the label of the side to move.
+    pub bold: bool,
+    /// Dim the pit-index rows.
+    pub dim: bool,
+}
+
+impl Default for DisplayStyle {
+    /// Cyan for A, magenta for B, with bold and dim on.
+    fn default() -> Self {
+        Self {
+            color_a: CYAN,
+            color_b: MAGENTA,
+            bold: true,
+            dim: true,
+        }
+    }
+}
+
+impl DisplayStyle {
+    /// No escape codes at all.
+    pub fn plain() -> Self {
+        Self {
+            color_a: "",
+            color_b: "",
+            bold: false,
+            dim: false,
+        }
+    }
+
+    /// Style used by `Display`: [`plain`](Self::plain) when the `NO_COLOR`
+    /// environment variable is set to a non-empty value, else the default.
+    pub fn from_env() -> Self {
+        Self::for_no_color(std::env::var_os("NO_COLOR").as_deref())
+    }
+
+    fn for_no_color(value: Option<&OsStr>) -> Self {
+        match value {
+            Some(v) if !v.is_empty() => Self::plain(),
+            _ => Self::default(),
+        }
+    }
+
+    fn color(&self, p: Player) -> &'static str {
+        match p {
+            Player::A => self.color_a,
+            Player::B => self.color_b,
+        }
+    }
+}
+
+/// `text` wrapped in `codes` and a reset, or bare when there are no codes.
+fn paint(codes: &str, text: impl Display) -> String {
+    if codes.is_empty() {
+        text.to_string()
+    } else {
+        format!("{codes}{text}{RESET}")
+    }
+}
+
 impl Display for Player {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Player::A => write!(f, "{CYAN}A{RESET}"),
-            Player::B => write!(f, "{MAGENTA}B{RESET}"),
-        }
+        let name = match self {
+            Player::A => "A",
+            Player::B => "B",
+        };
+        f.write_str(&paint(DisplayStyle::from_env().color(*self), name))
     }
 }
 
 impl Display for State {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let a = Player::A;
-        let b = Player::B;
+        self.render(&DisplayStyle::from_env(), f)
+    }
+}
+
+/// Same as [`State::oneline`].
+impl fmt::Debug for State {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.oneline())
+    }
+}
+
+impl State {
+    /// Compact single-line form without colors, e.g.
+    /// `A[4 4 4 4 4 4|0] B[4 4 4 4 4 4|0] *A`: each side's pits in index order
+    /// and its store, then the side to move.
+    pub fn oneline(&self) -> String {
+        let side = |p: Player| {
+            let pits: Vec<String> = self.pits(p).iter().map(u8::to_string).collect();
+            format!("[{}|{}]", pits.join(" "), self.store(p))
+        };
+        let to_move = match self.current_player() {
+            Player::A => 'A',
+            Player::B => 'B',
+        };
+        format!("A{} B{} *{to_move}", side(Player::A), side(Player::B))
+    }
 
-        let nums_b_plain = fmt_row_rev_plain(self.pits(b));
-        let nums_a_plain = fmt_row_plain(self.pits(a));
-        let idx_b_plain = fmt_idx_row_rev_plain();
-        let idx_a_plain = fmt_idx_row_plain();
+    /// The board as `Display` draws it, but in `style`.
+    pub fn render_with_style(&self, style: &DisplayStyle) -> String {
+        let mut out = String::new();
+        self.render(style, &mut out).expect("writing to a String");
+        out
+    }
 
-        let line1_plain = format!("|    B: [{}]     |", nums_b_plain);
-        let line1i_plain = format!("|    B: [{}]     |", idx_b_plain);
-        let line3_plain = format!("|    A: [{}]     |", nums_a_plain);
-        let line3i_plain = format!("|    A: [{}]     |", idx_a_plain);
+    fn render(&self, style: &DisplayStyle, f: &mut impl Write) -> fmt::Result {
+        let a = Player::A;
+        let b = Player::B;
+        let (col_a, col_b) = (style.color_a, style.color_b);
+        let dim = if style.dim { DIM } else { "" };
 
-        let target_width = line1_plain.len();
-        debug_assert_eq!(line3_plain.len(), target_width);
-        debug_assert_eq!(line1i_plain.len(), target_width);
-        debug_assert_eq!(line3i_plain.len(), target_width);
+        // Field widths grow with the largest value shown, so columns and the
+        // box stay aligned for any stone count (at least two characters).
+        let max_pit = self.pits(a).iter().chain(self.pits(b)).copied().max();
+        let pit_w = digits(max_pit.unwrap_or(0) as usize)
+            .max(digits(PITS_PER_SIDE - 1))
+            .max(2);
+        // A store can hold at most every stone in play, so size it for that:
+        // the box keeps one width for the whole game.
+        let store_w = digits(self.total_stones() as usize).max(2);
 
-        let store_b_plain = format!("[B:{:>2}]", self.store(b));
-        let store_a_plain = format!("[A:{:>2}]", self.store(a));
+        // Plain layout: "|    B: [" + cells + "]     |"
+        let cells_len = PITS_PER_SIDE * pit_w + (PITS_PER_SIDE - 1);
+        let row_len = 9 + cells_len + 7;
 
-        let inside_width = target_width - 2;
+        // Plain layout: "|" + pad + "[B:nn]" + gap + "[A:nn]" + pad + "|"
         let left_pad = 2usize;
         let right_pad = 2usize;
-        let core_min_plain = left_pad + store_b_plain.len() + store_a_plain.len() + right_pad;
-        let gap = inside_width.saturating_sub(core_min_plain);
+        let store_len = 4 + store_w;
+        let core_min_plain = left_pad + 2 * store_len + right_pad;
 
-        let label_a_col = if self.current_player() == a {
-            format!("{BOLD}{CYAN}A{RESET}")
-        } else {
-            format!("{CYAN}A{RESET}")
-        };
-        let label_b_col = if self.current_player() == b {
-            format!("{BOLD}{MAGENTA}B{RESET}")
-        } else {
-            format!("{MAGENTA}B{RESET}")
+        let target_width = row_len.max(core_min_plain + 2);
+        let row_tail = " ".repeat(5 + target_width - row_len);
+        let gap = target_width - 2 - core_min_plain;
+
+        let label = |p: Player, name: &str| {
+            if style.bold && self.current_player() == p {
+                paint(&format!("{BOLD}{}", style.color(p)), name)
+            } else {
+                paint(style.color(p), name)
+            }
         };
+        let label_a_col = label(a, "A");
+        let label_b_col = label(b, "B");
 
-        let nums_b_col = fmt_row_rev_col(self.pits(b), MAGENTA);
-        let nums_a_col = fmt_row_col(self.pits(a), CYAN);
-        let idx_b_col = fmt_idx_row_rev_col();
-        let idx_a_col = fmt_idx_row_col();
+        let nums_b_col = fmt_row_rev_col(self.pits(b), col_b, pit_w);
+        let nums_a_col = fmt_row_col(self.pits(a), col_a, pit_w);
+        let idx_b_col = fmt_idx_row_rev_col(dim, pit_w);
+        let idx_a_col = fmt_idx_row_col(dim, pit_w);
 
-        let line1_col = format!("|    {label_b_col}: [{}]     |", nums_b_col);
-        let line1i_col = format!("|    {label_b_col}: [{}]     |", idx_b_col);
-        let line3_col = format!("|    {label_a_col}: [{}]     |", nums_a_col);
-        let line3i_col = format!("|    {label_a_col}: [{}]     |", idx_a_col);
+        let line1_col = format!("|    {label_b_col}: [{nums_b_col}]{row_tail}|");
+        let line1i_col = format!("|    {label_b_col}: [{idx_b_col}]{row_tail}|");
+        let line3_col = format!("|    {label_a_col}: [{nums_a_col}]{row_tail}|");
+        let line3i_col = format!("|    {label_a_col}: [{idx_a_col}]{row_tail}|");
 
-        let store_b_col = format!("{MAGENTA}[B:{:>2}]{RESET}", self.store(b));
-        let store_a_col = format!("{CYAN}[A:{:>2}]{RESET}", self.store(a));
+        let store_b_col = paint(col_b, format_args!("[B:{:>store_w$}]", self.store(b)));
+        let store_a_col = paint(col_a, format_args!("[A:{:>store_w$}]", self.store(a)));
 
         let line2_col = format!(
             "|{}{}{}{}{}|",
@@ -86,88 +191,172 @@ impl Display for State {
     }
 }
 
-fn fmt_row_plain(pits: &[u8; PITS_PER_SIDE]) -> String {
+/// Decimal digits needed to print `n`.
+fn digits(n: usize) -> usize {
+    n.checked_ilog10().map_or(1, |d| d as usize + 1)
+}
+
+fn fmt_row_col(pits: &[u8; PITS_PER_SIDE], color: &str, w: usize) -> String {
     let mut s = String::new();
     for (i, v) in pits.iter().enumerate() {
         if i > 0 {
             s.push(' ');
         }
-        s.push_str(&format!("{:>2}", v));
+        s.push_str(&paint(color, format_args!("{:>w$}", v)));
     }
     s
 }
 
-fn fmt_row_rev_plain(pits: &[u8; PITS_PER_SIDE]) -> String {
+fn fmt_row_rev_col(pits: &[u8; PITS_PER_SIDE], color: &str, w: usize) -> String {
     let mut s = String::new();
     for (k, i) in (0..PITS_PER_SIDE).rev().enumerate() {
         if k > 0 {
             s.push(' ');
         }
-        s.push_str(&format!("{:>2}", pits[i]));
+        s.push_str(&paint(color, format_args!("{:>w$}", pits[i])));
     }
     s
 }
 
-fn fmt_row_col(pits: &[u8; PITS_PER_SIDE], color: &str) -> String {
+fn fmt_idx_row_col(dim: &str, w: usize) -> String {
     let mut s = String::new();
-    for (i, v) in pits.iter().enumerate() {
+    for i in 0..PITS_PER_SIDE {
         if i > 0 {
             s.push(' ');
         }
-        s.push_str(&format!("{color}{:>2}{RESET}", v));
+        s.push_str(&paint(dim, format_args!("{:>w$}", i)));
     }
     s
 }
 
-fn fmt_row_rev_col(pits: &[u8; PITS_PER_SIDE], color: &str) -> String {
+fn fmt_idx_row_rev_col(dim: &str, w: usize) -> String {
     let mut s = String::new();
     for (k, i) in (0..PITS_PER_SIDE).rev().enumerate() {
         if k > 0 {
             s.push(' ');
         }
-        s.push_str(&format!("{color}{:>2}{RESET}", pits[i]));
+        s.push_str(&paint(dim, format_args!("{:>w$}", i)));
     }
     s
 }
 
-fn fmt_idx_row_plain() -> String {
-    let mut s = String::new();
-    for i in 0..PITS_PER_SIDE {
-        if i > 0 {
-            s.push(' ');
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StateBuilder;
+
+    /// Drop ANSI escape sequences (`ESC [ ... m`).
+    fn strip_ansi(s: &str) -> String {
+        let mut out = String::new();
+        let mut in_esc = false;
+        for c in s.chars() {
+            match (in_esc, c) {
+                (false, '\x1b') => in_esc = true,
+                (true, 'm') => in_esc = false,
+                (true, _) => {}
+                (false, c) => out.push(c),
+            }
         }
-        s.push_str(&format!("{:>2}", i));
+        out
     }
-    s
-}
-fn fmt_idx_row_col() -> String {
-    let mut s = String::new();
-    for i in 0..PITS_PER_SIDE {
-        if i > 0 {
-            s.push(' ');
-        }
-        s.push_str(&format!("{DIM}{:>2}{RESET}", i));
+
+    fn row_widths(s: &State) -> Vec<usize> {
+        strip_ansi(&s.to_string())
+            .lines()
+            .map(|l| l.chars().count())
+            .collect()
     }
-    s
-}
 
-fn fmt_idx_row_rev_plain() -> String {
-    let mut s = String::new();
-    for (k, i) in (0..PITS_PER_SIDE).rev().enumerate() {
-        if k > 0 {
-            s.push(' ');
-        }
-        s.push_str(&format!("{:>2}", i));
+    #[test]
+    fn opening_rows_are_aligned() {
+        let w = row_widths(&State::new());
+        assert_eq!(w.len(), 5);
+        assert!(w.iter().all(|&x| x == w[0]));
     }
-    s
-}
-fn fmt_idx_row_rev_col() -> String {
-    let mut s = String::new();
-    for (k, i) in (0..PITS_PER_SIDE).rev().enumerate() {
-        if k > 0 {
-            s.push(' ');
-        }
-        s.push_str(&format!("{DIM}{:>2}{RESET}", i));
+
+    #[test]
+    fn three_digit_store_keeps_rows_aligned() {
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 0, 1)
+            .store(Player::A, 120)
+            .store(Player::B, 7)
+            .build()
+            .unwrap();
+        let w = row_widths(&s);
+        assert!(w.iter().all(|&x| x == w[0]), "{w:?}");
+        assert!(strip_ansi(&s.to_string()).contains("[A:120]"));
+    }
+
+    #[test]
+    fn store_width_follows_total_stones() {
+        let s = StateBuilder::new()
+            .pits(Player::A, [20; PITS_PER_SIDE])
+            .pit(Player::B, 0, 1)
+            .store(Player::A, 5)
+            .build()
+            .unwrap();
+        let out = strip_ansi(&s.to_string());
+        assert!(out.contains("[A:  5]") && out.contains("[B:  0]"), "{out}");
+
+        // the box keeps its width as stones move
+        let w = row_widths(&s);
+        let next = s.child_after_move(0).unwrap();
+        assert!(row_widths(&next).iter().all(|&x| x == w[0]));
+    }
+
+    #[test]
+    fn three_digit_pit_keeps_rows_aligned() {
+        let s = StateBuilder::new()
+            .pit(Player::A, 3, 150)
+            .pit(Player::B, 0, 1)
+            .store(Player::B, 100)
+            .build()
+            .unwrap();
+        let w = row_widths(&s);
+        assert!(w.iter().all(|&x| x == w[0]), "{w:?}");
+    }
+
+    #[test]
+    fn no_color_renders_without_escapes() {
+        let style = DisplayStyle::for_no_color(Some(OsStr::new("1")));
+        assert_eq!(style, DisplayStyle::plain());
+        let out = State::new().render_with_style(&style);
+        assert!(!out.contains('\x1b'));
+        assert_eq!(
+            out,
+            strip_ansi(&State::new().render_with_style(&DisplayStyle::default()))
+        );
+
+        // empty means unset
+        let style = DisplayStyle::for_no_color(Some(OsStr::new("")));
+        assert_eq!(style, DisplayStyle::default());
+    }
+
+    #[test]
+    fn custom_style_uses_given_codes() {
+        let green = "\x1b[32m";
+        let yellow = "\x1b[33m";
+        let style = DisplayStyle {
+            color_a: green,
+            color_b: yellow,
+            bold: false,
+            dim: false,
+        };
+        let out = State::new().render_with_style(&style);
+        assert!(out.contains(&format!("{green}[A: 0]{RESET}")));
+        assert!(out.contains(&format!("{yellow}[B: 0]{RESET}")));
+        assert!(!out.contains(CYAN) && !out.contains(MAGENTA));
+        assert!(!out.contains(BOLD) && !out.contains(DIM));
+    }
+
+    #[test]
+    fn oneline_of_opening() {
+        assert_eq!(
+            State::new().oneline(),
+            "A[4 4 4 4 4 4|0] B[4 4 4 4 4 4|0] *A"
+        );
+        let s = State::new().child_after_move(0).unwrap();
+        assert_eq!(format!("{s:?}"), "A[0 5 5 5 5 4|0] B[4 4 4 4 4 4|0] *B");
     }
-    s
 }
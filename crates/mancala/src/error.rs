@@ -0,0 +1,50 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Why a move could not be applied.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Error {
+    /// The position is terminal; no moves remain.
+    GameOver,
+    /// The pit is on the board but is not a legal move: it holds no
+    /// stones, or the rules rule it out (see
+    /// [`State::legal_moves`](crate::State::legal_moves)).
+    IllegalMove,
+    /// The pit index is not below `PITS_PER_SIDE`.
+    OutOfBounds,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::GameOver => write!(f, "game is over"),
+            Error::IllegalMove => write!(f, "move is not legal here"),
+            Error::OutOfBounds => write!(f, "pit index out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Why a position fails [`State::validate`](crate::State::validate).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum InvalidState {
+    /// More stones than a single cell can ever hold (`u8::MAX`), so sowing
+    /// or sweeping could overflow a store.
+    TooManyStones(u16),
+    /// One side is empty but the other still holds stones; the engine always
+    /// sweeps such positions, so it is unreachable and its outcome ambiguous.
+    UnsweptTerminal,
+}
+
+impl Display for InvalidState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidState::TooManyStones(n) => {
+                write!(f, "{n} stones exceed the {} limit", u8::MAX)
+            }
+            InvalidState::UnsweptTerminal => write!(f, "terminal position was not swept"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidState {}
@@ -0,0 +1,106 @@
+//! Step-by-step account of a move, for animating or logging play.
+
+use crate::{Player, State};
+
+/// A place stones can be: one of `side`'s pits, or its store.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Loc {
+    Pit { side: Player, idx: usize },
+    Store { side: Player },
+}
+
+/// Something that happened while a move was played, in order.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum MoveEvent {
+    /// One stone dropped at `loc`.
+    Sow { loc: Loc },
+    /// The last stone landed in the mover's empty `pit`; it and the
+    /// `stones` opposite, taken `from` the opponent, went to the mover's
+    /// store.
+    Capture {
+        pit: usize,
+        stones: u8,
+        from: Player,
+    },
+    /// The mover goes again: the last stone landed in its store (any
+    /// [`Rules::extra_turn_bonus`](crate::Rules::extra_turn_bonus) has been
+    /// added), or it captured under
+    /// [`Rules::capture_grants_extra_turn`](crate::Rules::capture_grants_extra_turn).
+    ExtraTurn,
+    /// The game ended and `stones` left on `side`'s pits went to its store.
+    /// Only sides with stones left get one.
+    Sweep { side: Player, stones: u8 },
+}
+
+impl State {
+    /// Like [`child_after_move`](Self::child_after_move), also returning
+    /// what happened along the way.
+    pub fn apply_move_events(&self, pit: usize) -> Option<(State, Vec<MoveEvent>)> {
+        self.try_child_after_move(pit).ok()?;
+        let mut s = self.clone();
+        let mut events = Vec::new();
+        s.sow_from_pit(pit, |e| events.push(e));
+        Some((s, events))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PITS_PER_SIDE, StateBuilder};
+
+    #[test]
+    fn capture_events_in_order() {
+        let s = StateBuilder::new()
+            .pit(Player::A, 1, 1)
+            .pit(Player::A, 5, 2)
+            .pit(Player::B, PITS_PER_SIDE - 1 - 2, 5)
+            .pit(Player::B, 0, 3)
+            .build()
+            .unwrap();
+        let (next, events) = s.apply_move_events(1).unwrap();
+        assert_eq!(
+            events,
+            [
+                MoveEvent::Sow {
+                    loc: Loc::Pit {
+                        side: Player::A,
+                        idx: 2
+                    }
+                },
+                MoveEvent::Capture {
+                    pit: 2,
+                    stones: 5,
+                    from: Player::B
+                },
+            ]
+        );
+        assert!(next == s.child_after_move(1).unwrap());
+        assert_eq!(next.store(Player::A), 6);
+    }
+
+    #[test]
+    fn extra_turn_and_sweep() {
+        let s = StateBuilder::new()
+            .pit(Player::A, PITS_PER_SIDE - 1, 1)
+            .pit(Player::B, 0, 4)
+            .build()
+            .unwrap();
+        let (next, events) = s.apply_move_events(PITS_PER_SIDE - 1).unwrap();
+        assert_eq!(
+            events,
+            [
+                MoveEvent::Sow {
+                    loc: Loc::Store { side: Player::A }
+                },
+                MoveEvent::ExtraTurn,
+                MoveEvent::Sweep {
+                    side: Player::B,
+                    stones: 4
+                },
+            ]
+        );
+        assert!(next.is_terminal());
+        assert!(s.apply_move_events(0).is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! Compact text notation for positions.
+//!
+//! `<A pits>/<B pits> <A store> <B store> <to move>`, pits comma-separated
+//! from index 0, e.g. the opening is `4,4,4,4,4,4/4,4,4,4,4,4 0 0 A`.
+//! Rules are not recorded; parsed positions use the defaults.
+
+use std::fmt::{self, Display, Formatter};
+
+use crate::{BuildError, PITS_PER_SIDE, Player, State, StateBuilder};
+
+/// Why a string could not be read as a position.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FenError {
+    /// The text does not follow the notation.
+    Malformed,
+    /// The text parsed but describes a position the engine rejects.
+    Invalid(BuildError),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::Malformed => write!(f, "malformed position string"),
+            FenError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+impl State {
+    /// This position in FEN-like notation (see the module docs).
+    pub fn to_fen(&self) -> String {
+        let side = |p: Player| {
+            self.pits(p)
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let to_move = match self.current_player() {
+            Player::A => 'A',
+            Player::B => 'B',
+        };
+        format!(
+            "{}/{} {} {} {to_move}",
+            side(Player::A),
+            side(Player::B),
+            self.store(Player::A),
+            self.store(Player::B)
+        )
+    }
+
+    /// Parse [`to_fen`](Self::to_fen) output. The position must pass
+    /// [`validate`](Self::validate).
+    pub fn from_fen(fen: &str) -> Result<State, FenError> {
+        let mut fields = fen.split_whitespace();
+        let (Some(board), Some(store_a), Some(store_b), Some(to_move), None) = (
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+            fields.next(),
+        ) else {
+            return Err(FenError::Malformed);
+        };
+        let (pits_a, pits_b) = board.split_once('/').ok_or(FenError::Malformed)?;
+        let to_move = match to_move {
+            "A" => Player::A,
+            "B" => Player::B,
+            _ => return Err(FenError::Malformed),
+        };
+        StateBuilder::new()
+            .pits(Player::A, parse_pits(pits_a)?)
+            .pits(Player::B, parse_pits(pits_b)?)
+            .store(Player::A, store_a.parse().map_err(|_| FenError::Malformed)?)
+            .store(Player::B, store_b.parse().map_err(|_| FenError::Malformed)?)
+            .to_move(to_move)
+            .build()
+            .map_err(FenError::Invalid)
+    }
+}
+
+fn parse_pits(s: &str) -> Result<[u8; PITS_PER_SIDE], FenError> {
+    let mut pits = [0; PITS_PER_SIDE];
+    let mut it = s.split(',');
+    for p in &mut pits {
+        let v = it.next().ok_or(FenError::Malformed)?;
+        *p = v.parse().map_err(|_| FenError::Malformed)?;
+    }
+    if it.next().is_some() {
+        return Err(FenError::Malformed);
+    }
+    Ok(pits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InvalidState;
+
+    #[test]
+    fn opening_round_trips() {
+        let s = State::new();
+        assert_eq!(s.to_fen(), "4,4,4,4,4,4/4,4,4,4,4,4 0 0 A");
+        assert!(State::from_fen(&s.to_fen()).unwrap() == s);
+    }
+
+    #[test]
+    fn midgame_round_trips() {
+        let mut s = State::new();
+        for m in [2, 5, 1] {
+            s = s.child_after_move(m).unwrap();
+            assert!(State::from_fen(&s.to_fen()).unwrap() == s);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_text() {
+        for bad in [
+            "",
+            "4,4,4,4,4,4/4,4,4,4,4,4 0 0",
+            "4,4,4,4,4,4/4,4,4,4,4,4 0 0 C",
+            "4,4,4,4,4/4,4,4,4,4,4 0 0 A",
+            "4,4,4,4,4,4,4/4,4,4,4,4,4 0 0 A",
+            "4,4,4,4,4,x/4,4,4,4,4,4 0 0 A",
+            "4,4,4,4,4,4 4,4,4,4,4,4 0 0 A",
+            "4,4,4,4,4,4/4,4,4,4,4,4 0 0 A extra",
+        ] {
+            assert_eq!(
+                State::from_fen(bad).err(),
+                Some(FenError::Malformed),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_position() {
+        let r = State::from_fen("1,0,0,0,0,0/0,0,0,0,0,0 0 0 A");
+        assert_eq!(
+            r.err(),
+            Some(FenError::Invalid(BuildError::Invalid(
+                InvalidState::UnsweptTerminal
+            )))
+        );
+    }
+}
@@ -2,18 +2,40 @@
 //!
 //! Public API surface is intentionally small:
 //! - [`State`]: immutable game state
+//! - [`StateBuilder`]: set up arbitrary positions
 //! - [`State::legal_actions`]: enumerate successor states
 //! - helpers: terminal check, winner, score, legal moves
 //!
-//! Rules are fixed by crate-level constants.
+//! Board size is fixed by crate-level constants; rule variants are
+//! selected per game with [`Rules`].
 
+pub mod analysis;
+pub mod batch;
+mod builder;
+mod bytes;
 mod constants;
 mod display;
+mod error;
+mod event;
+mod fen;
 mod outcome;
 mod player;
+mod rules;
+pub mod search;
+mod sow_table;
 mod state;
+mod zobrist;
 
-pub use constants::{PITS_PER_SIDE, STONES_PER_PIT};
-pub use outcome::Outcome;
-pub use player::Player;
-pub use state::State;
+pub use builder::{BuildError, StateBuilder};
+pub use bytes::{DecodeError, STATE_BYTES};
+pub use constants::{
+    INITIAL_TOTAL_STONES, MAX_ACTIONS, PITS_PER_SIDE, STONES_PER_PIT, TOTAL_CELLS,
+};
+pub use display::DisplayStyle;
+pub use error::{Error, InvalidState};
+pub use event::{Loc, MoveEvent};
+pub use fen::FenError;
+pub use outcome::{GameSummary, Outcome};
+pub use player::{PLAYERS, Player};
+pub use rules::{GrandSlam, Rules};
+pub use state::{State, infer_move, random_playout, random_playout_len};
@@ -1,6 +1,30 @@
+use super::Player;
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum Outcome {
     Ongoing,
-    Win(super::Player),
+    Win(Player),
     Draw,
 }
+
+/// Result of a finished game, see [`State::game_summary`](super::State::game_summary).
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct GameSummary {
+    /// `None` on a draw.
+    pub winner: Option<Player>,
+    /// Final `(A, B)` stores.
+    pub stores: (u8, u8),
+    /// Winner's store minus the loser's.
+    pub margin: u8,
+    /// Moves played, when the caller knows it.
+    pub moves: Option<usize>,
+}
+
+impl GameSummary {
+    pub fn with_moves(self, moves: usize) -> Self {
+        Self {
+            moves: Some(moves),
+            ..self
+        }
+    }
+}
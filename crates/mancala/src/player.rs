@@ -4,7 +4,22 @@ pub enum Player {
     B,
 }
 
+/// Both players, in index order.
+pub const PLAYERS: [Player; 2] = [Player::A, Player::B];
+
 impl Player {
+    /// Both players, `A` then `B`.
+    #[inline]
+    pub fn all() -> [Player; 2] {
+        PLAYERS
+    }
+
+    /// Iterate over both players, `A` then `B`.
+    #[inline]
+    pub fn both() -> impl Iterator<Item = Player> {
+        PLAYERS.into_iter()
+    }
+
     #[inline]
     pub fn opponent(self) -> Player {
         match self {
@@ -21,3 +36,17 @@ impl Player {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_yields_a_then_b() {
+        assert_eq!(Player::all(), [Player::A, Player::B]);
+        assert_eq!(Player::both().collect::<Vec<_>>(), PLAYERS.to_vec());
+        for (i, p) in Player::all().into_iter().enumerate() {
+            assert_eq!(p.idx(), i);
+        }
+    }
+}
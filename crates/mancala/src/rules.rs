@@ -0,0 +1,35 @@
+/// Optional rule variants layered over standard Kalah.
+///
+/// `Rules::default()` is the standard game.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Rules {
+    /// Stones added to the mover's store when the last stone lands there.
+    /// Bonus stones come from an unbounded reserve, so the stone total grows
+    /// by this amount on every extra turn, up to `u8::MAX` stones in all;
+    /// past that the bonus is cut to what fits.
+    pub extra_turn_bonus: u8,
+    /// When the opponent's side is empty, the mover must play a move that
+    /// sows into it; the game ends (with a sweep) only if no such move
+    /// exists.
+    pub feeding_required: bool,
+    /// A capture also earns the mover another turn, like a last stone in
+    /// the store.
+    pub capture_grants_extra_turn: bool,
+    /// What happens to a capture that would leave the opponent's side
+    /// empty.
+    pub grand_slam: GrandSlam,
+}
+
+/// Treatment of a "grand slam": a capture that takes every stone left on
+/// the opponent's side.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub enum GrandSlam {
+    /// The capture happens as usual.
+    #[default]
+    Allowed,
+    /// Such moves are not legal. If every move is one, the position is a
+    /// [stalemate](crate::State::is_stalemate).
+    Forbidden,
+    /// The move is played but captures nothing.
+    NoCapture,
+}
@@ -0,0 +1,88 @@
+//! Plain fixed-depth negamax, a reference for checking faster searchers.
+
+use crate::State;
+
+/// Value of `state` for its side to move after searching `depth` plies,
+/// scoring the horizon and finished games (stalemates included) by
+/// [`score_for`](State::score_for) the side to move there. An extra turn
+/// uses up a ply but does not flip the sign.
+pub fn negamax(state: &State, depth: u32) -> i32 {
+    if depth == 0 || state.is_terminal() || state.is_stalemate() {
+        return state.score_for(state.current_player());
+    }
+    state
+        .legal_actions()
+        .iter()
+        .map(|child| child_value(state, child, depth - 1))
+        .max()
+        .expect("non-terminal positions have a move")
+}
+
+/// The move [`negamax`] rates highest, with that value; ties go to the
+/// lower pit. `None` on a finished game.
+pub fn best_move(state: &State, depth: u32) -> Option<(usize, i32)> {
+    let mut best: Option<(usize, i32)> = None;
+    for (m, child) in state.legal_transitions() {
+        let v = child_value(state, &child, depth.saturating_sub(1));
+        if best.is_none_or(|(_, b)| v > b) {
+            best = Some((m, v));
+        }
+    }
+    best
+}
+
+fn child_value(parent: &State, child: &State, depth: u32) -> i32 {
+    let v = negamax(child, depth);
+    if child.current_player() == parent.current_player() {
+        v
+    } else {
+        -v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GrandSlam, PITS_PER_SIDE, Player, Rules, StateBuilder};
+
+    #[test]
+    fn stalemate_scores_the_stores() {
+        // A's only move would take all of B's stones, which is forbidden
+        let s = StateBuilder::new()
+            .pit(Player::A, 0, 1)
+            .pit(Player::B, 4, 3)
+            .store(Player::A, 23)
+            .store(Player::B, 21)
+            .rules(Rules {
+                grand_slam: GrandSlam::Forbidden,
+                ..Rules::default()
+            })
+            .build()
+            .unwrap();
+        assert!(s.is_stalemate());
+        assert_eq!(negamax(&s, 3), 2);
+        assert_eq!(best_move(&s, 3), None);
+    }
+
+    #[test]
+    fn depth_one_takes_the_biggest_immediate_gain() {
+        // pit 5 reaches the store (+1, extra turn); pit 1 captures 5 (+6)
+        let s = StateBuilder::new()
+            .pit(Player::A, 1, 1)
+            .pit(Player::A, 5, 1)
+            .pit(Player::B, PITS_PER_SIDE - 1 - 2, 5)
+            .pit(Player::B, 0, 2)
+            .build()
+            .unwrap();
+        assert_eq!(negamax(&s, 0), 0);
+        assert_eq!(negamax(&s, 1), 6);
+        assert_eq!(best_move(&s, 1), Some((1, 6)));
+    }
+
+    #[test]
+    fn finished_games_score_as_they_stand() {
+        let over = StateBuilder::new().store(Player::B, 5).build().unwrap();
+        assert_eq!(negamax(&over, 3), -5);
+        assert_eq!(best_move(&over, 3), None);
+    }
+}
@@ -0,0 +1,55 @@
+//! Precomputed sowing for the standard game.
+//!
+//! Where `n` stones from a given pit end up depends only on the pit and `n`,
+//! so for every count a standard board can hold the per-location increments
+//! are built at compile time. Locations are numbered from the mover's view:
+//! `0..P` its pits, `P` its store, `P + 1..=2P` the opponent's pits (the
+//! opponent's store is never sown).
+
+use crate::{PITS_PER_SIDE, Rules, STONES_PER_PIT};
+
+/// Sown locations per lap.
+pub(crate) const LAP: usize = 2 * PITS_PER_SIDE + 1;
+
+/// Stones on a standard board; larger pits go through the general engine.
+const MAX_STONES: usize = 2 * PITS_PER_SIDE * STONES_PER_PIT as usize;
+
+#[derive(Copy, Clone)]
+pub(crate) struct Sowing {
+    /// Stones added to each location.
+    pub(crate) deltas: [u8; LAP],
+    /// Location of the last stone.
+    pub(crate) last: usize,
+}
+
+static TABLE: [[Sowing; MAX_STONES + 1]; PITS_PER_SIDE] = build();
+
+const fn build() -> [[Sowing; MAX_STONES + 1]; PITS_PER_SIDE] {
+    let empty = Sowing {
+        deltas: [0; LAP],
+        last: 0,
+    };
+    let mut table = [[empty; MAX_STONES + 1]; PITS_PER_SIDE];
+    let mut pit = 0;
+    while pit < PITS_PER_SIDE {
+        let mut n = 1;
+        while n <= MAX_STONES {
+            let mut entry = table[pit][n - 1];
+            entry.last = (pit + n) % LAP;
+            entry.deltas[entry.last] += 1;
+            table[pit][n] = entry;
+            n += 1;
+        }
+        pit += 1;
+    }
+    table
+}
+
+/// The table entry for sowing `stones` from `pit`, if `rules` are the
+/// standard ones and the count is in range.
+pub(crate) fn lookup(rules: Rules, pit: usize, stones: u8) -> Option<&'static Sowing> {
+    if rules != Rules::default() {
+        return None;
+    }
+    TABLE.get(pit)?.get(stones as usize)
+}
@@ -1,12 +1,51 @@
-use crate::{Outcome, PITS_PER_SIDE, Player, STONES_PER_PIT};
+use crate::event::{Loc, MoveEvent};
+use crate::sow_table::{self, LAP, Sowing};
+use crate::zobrist::{pit_key, side_key, store_key};
+use crate::{
+    Error, GameSummary, GrandSlam, InvalidState, Outcome, PITS_PER_SIDE, Player, Rules,
+    STONES_PER_PIT, TOTAL_CELLS,
+};
+use rand::Rng;
+use rand::seq::IndexedRandom;
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 
 /// Immutable Mancala position.
-#[derive(Clone, Eq, PartialEq, Hash)]
+///
+/// Equality and hashing look at the position only, not at
+/// [`last_move`](Self::last_move) or [`captures`](Self::captures), so
+/// transpositions compare equal.
+#[derive(Clone)]
 pub struct State {
     pits: [[u8; PITS_PER_SIDE]; 2],
     stores: [u8; 2],
     to_move: Player,
+    rules: Rules,
+    /// Running zobrist hash, kept in sync by the engine.
+    hash: u64,
+    /// Pit played to reach this position, for display only.
+    last_move: Option<usize>,
+    /// Opponent stones each side has captured on the way here.
+    captured: [u16; 2],
+}
+
+impl PartialEq for State {
+    fn eq(&self, other: &Self) -> bool {
+        self.pits == other.pits
+            && self.stores == other.stores
+            && self.to_move == other.to_move
+            && self.rules == other.rules
+    }
+}
+
+impl Eq for State {}
+
+impl Hash for State {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the zobrist hash already covers pits, stores and side to move
+        self.hash.hash(state);
+        self.rules.hash(state);
+    }
 }
 
 impl Default for State {
@@ -18,19 +57,70 @@ impl Default for State {
 impl State {
     /// Standard initial position.
     pub fn new() -> Self {
-        Self {
-            pits: [[STONES_PER_PIT; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        }
+        Self::new_with_mover(Player::A)
+    }
+
+    /// Standard initial position with `first` to move.
+    pub fn new_with_mover(first: Player) -> Self {
+        Self::from_parts([[STONES_PER_PIT; PITS_PER_SIDE]; 2], [0, 0], first)
+    }
+
+    /// Initial position played under `rules`.
+    pub fn with_rules(rules: Rules) -> Self {
+        let mut s = Self::new();
+        s.set_rules(rules);
+        s
+    }
+
+    pub(crate) fn from_parts(
+        pits: [[u8; PITS_PER_SIDE]; 2],
+        stores: [u8; 2],
+        to_move: Player,
+    ) -> Self {
+        let mut s = Self {
+            pits,
+            stores,
+            to_move,
+            rules: Rules::default(),
+            hash: 0,
+            last_move: None,
+            captured: [0, 0],
+        };
+        s.hash = s.compute_hash();
+        s
+    }
+
+    #[inline]
+    pub(crate) fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
     }
 
     /// Whose turn it is.
+    ///
+    /// After the game-ending move this is still decided by the extra-turn
+    /// rule: the player who just moved if the last stone landed in their
+    /// store, the opponent otherwise. The end-of-game sweep never changes it.
+    ///
+    /// So on a finished game this names no one who can actually move; check
+    /// [`is_terminal`](Self::is_terminal) first, or use
+    /// [`side_to_move`](Self::side_to_move).
     #[inline]
     pub fn current_player(&self) -> Player {
         self.to_move
     }
 
+    /// The player who must move now, or `None` once the game is over.
+    #[inline]
+    pub fn side_to_move(&self) -> Option<Player> {
+        (!self.is_terminal()).then_some(self.to_move)
+    }
+
+    /// Rule variant this position is played under.
+    #[inline]
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
     /// Small pits for a side (read-only).
     #[inline]
     pub fn pits(&self, side: Player) -> &[u8; PITS_PER_SIDE] {
@@ -43,14 +133,204 @@ impl State {
         self.stores[side.idx()]
     }
 
-    /// Legal moves as pit indices on the current side.
-    pub fn legal_moves(&self) -> Vec<usize> {
-        if self.is_terminal() {
-            return Vec::new();
+    /// `(A's store, B's store)`.
+    pub fn stores(&self) -> (u8, u8) {
+        (self.stores[0], self.stores[1])
+    }
+
+    /// The pit whose move produced this position, or `None` for a position
+    /// that was set up rather than played into. Ignored by `==`.
+    pub fn last_move(&self) -> Option<usize> {
+        self.last_move
+    }
+
+    /// Opponent stones `side` has taken by captures in the moves leading
+    /// here, not counting its own capturing stone. Positions that were set
+    /// up start from zero. Ignored by `==`.
+    pub fn captures(&self, side: Player) -> u16 {
+        self.captured[side.idx()]
+    }
+
+    /// The side with more stones in its store, or `None` when level.
+    pub fn leader(&self) -> Option<Player> {
+        match self.stores[0].cmp(&self.stores[1]) {
+            Ordering::Greater => Some(Player::A),
+            Ordering::Less => Some(Player::B),
+            Ordering::Equal => None,
         }
+    }
+
+    /// Stores `(A, B)` as they would be if every pit were swept to its owner
+    /// now. Equals the actual stores on terminal positions.
+    pub fn preview_sweep(&self) -> (u8, u8) {
+        let swept = |i: usize| self.stores[i] + self.pits[i].iter().sum::<u8>();
+        (swept(0), swept(1))
+    }
+
+    /// Legal moves as pit indices on the current side. Under
+    /// [`Rules::feeding_required`], only moves that reach an empty opponent's
+    /// side; under [`GrandSlam::Forbidden`], no move that would capture the
+    /// opponent's whole side.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        self.legal_pits().collect()
+    }
+
+    /// [`legal_moves`](Self::legal_moves) into `buf`, replacing its contents
+    /// and reusing its allocation.
+    pub fn legal_moves_into(&self, buf: &mut Vec<usize>) {
+        buf.clear();
+        buf.extend(self.legal_pits());
+    }
+
+    fn legal_pits(&self) -> impl Iterator<Item = usize> + '_ {
+        let over = self.is_terminal();
         let side = self.to_move.idx();
-        (0..PITS_PER_SIDE)
-            .filter(|&i| self.pits[side][i] > 0)
+        let must_feed = !over && self.awaiting_feed();
+        let no_slam = self.rules.grand_slam == GrandSlam::Forbidden;
+        (0..PITS_PER_SIDE).filter(move |&i| {
+            !over
+                && self.pits[side][i] > 0
+                && (!must_feed || self.feeds(i))
+                && !(no_slam && self.move_grand_slams(i))
+        })
+    }
+
+    /// Playing `pit` (which must hold stones) ends in a capture of every
+    /// stone left on the opponent's side.
+    fn move_grand_slams(&self, pit: usize) -> bool {
+        let mut next = self.clone();
+        let stones = next.pits[next.to_move.idx()][pit];
+        next.set_pit(next.to_move.idx(), pit, 0);
+        let last = next.sow(pit, stones, &mut |_| {});
+        next.grand_slam_at(last)
+    }
+
+    /// The last stone landing at `last` would capture, and the capture
+    /// would take all of the opponent's remaining stones.
+    fn grand_slam_at(&self, last: Loc) -> bool {
+        let Loc::Pit { side, idx } = last else {
+            return false;
+        };
+        if side != self.to_move || self.pits[side.idx()][idx] != 1 {
+            return false;
+        }
+        let (opp, opp_idx) = Self::capture_partner(side, idx);
+        let across = self.pits[opp.idx()][opp_idx];
+        across > 0 && across == self.pits[opp.idx()].iter().sum::<u8>()
+    }
+
+    /// The pit across the board from `side`'s `pit`, whose stones a capture
+    /// there takes. Pits are indexed in sowing order on both sides, so the
+    /// partner's index counts from the other end.
+    pub fn capture_partner(side: Player, pit: usize) -> (Player, usize) {
+        debug_assert!(pit < PITS_PER_SIDE);
+        (side.opponent(), PITS_PER_SIDE - 1 - pit)
+    }
+
+    /// Cells on the sowing ring: every pit and both stores.
+    pub const RING_LEN: usize = TOTAL_CELLS;
+
+    /// Absolute ring position of `side`'s pit `pit` in sowing order: A's pits,
+    /// A's store, B's pits, B's store. Sowing advances one position per
+    /// stone, skipping the opponent's store.
+    pub fn ring_position(side: Player, pit: usize) -> usize {
+        debug_assert!(pit < PITS_PER_SIDE);
+        side.idx() * (PITS_PER_SIDE + 1) + pit
+    }
+
+    /// Ring position of `side`'s store; see [`ring_position`](Self::ring_position).
+    pub fn store_ring_position(side: Player) -> usize {
+        side.idx() * (PITS_PER_SIDE + 1) + PITS_PER_SIDE
+    }
+
+    /// [`legal_moves`](Self::legal_moves) as a bitmask: bit `i` is set when
+    /// pit `i` is legal.
+    pub fn legal_moves_mask(&self) -> u16 {
+        self.legal_moves().into_iter().fold(0, |m, i| m | 1 << i)
+    }
+
+    /// Whether `next`, the result of playing `pit` here, has the side to
+    /// move the extra-turn rule requires: the same player exactly when
+    /// [`move_gives_extra_turn`](Self::move_gives_extra_turn), even if the
+    /// move also ended the game (see [`current_player`](Self::current_player)).
+    pub fn kept_turn_is_valid(&self, pit: usize, next: &State) -> bool {
+        (next.to_move == self.to_move) == self.move_gives_extra_turn(pit)
+    }
+
+    /// Whether playing `pit` is legal and ends in the mover's store, granting
+    /// another move (or captures, under
+    /// [`Rules::capture_grants_extra_turn`]).
+    pub fn move_gives_extra_turn(&self, pit: usize) -> bool {
+        if !self.legal_moves().contains(&pit) {
+            return false;
+        }
+        // one lap passes every pit and the mover's store, not the opponent's
+        let lap = 2 * PITS_PER_SIDE + 1;
+        let to_store = PITS_PER_SIDE - pit;
+        let stones = self.pits[self.to_move.idx()][pit] as usize;
+        if stones >= to_store && (stones - to_store).is_multiple_of(lap) {
+            return true;
+        }
+        self.rules.capture_grants_extra_turn && self.move_captures(pit)
+    }
+
+    /// The legal moves that capture, in ascending pit order.
+    pub fn capturing_moves(&self) -> Vec<usize> {
+        self.legal_pits()
+            .filter(|&m| self.move_captures(m))
+            .collect()
+    }
+
+    /// The legal moves after which the opponent has no capturing reply.
+    /// Moves that keep the turn, or end the game, count as safe since the
+    /// opponent does not move next.
+    pub fn safe_moves(&self) -> Vec<usize> {
+        self.legal_transitions()
+            .into_iter()
+            .filter(|(_, next)| next.to_move == self.to_move || next.capturing_moves().is_empty())
+            .map(|(m, _)| m)
+            .collect()
+    }
+
+    /// Playing `pit` (which must hold stones) captures.
+    fn move_captures(&self, pit: usize) -> bool {
+        let mut captured = false;
+        self.clone()
+            .sow_from_pit(pit, |e| captured |= matches!(e, MoveEvent::Capture { .. }));
+        captured
+    }
+
+    /// Whether sowing the mover's `pit` goes all the way round and drops a
+    /// stone back on the mover's side at or before `pit`: the stones cover
+    /// the later pits, the store and the opponent's pits, then reach pit 0.
+    /// `None` if `pit` is out of range or empty.
+    pub fn move_wraps_around(&self, pit: usize) -> Option<bool> {
+        let stones = *self.pits[self.to_move.idx()].get(pit)? as usize;
+        if stones == 0 {
+            return None;
+        }
+        // pit + 1 ..= P - 1, the store, P opponent pits, then own pit 0
+        Some(stones > 2 * PITS_PER_SIDE - pit)
+    }
+
+    /// The lowest opening pit that grants another move under `rules`, if
+    /// any. Board size and stones per pit are crate constants, so at the
+    /// standard 6/4 this is pit 2 for every [`Rules`].
+    pub fn extra_turn_pit_on_fresh_board(rules: Rules) -> Option<usize> {
+        Self::with_rules(rules).extra_turn_moves().first().copied()
+    }
+
+    /// Whether some legal move grants another move; `true` exactly when
+    /// [`extra_turn_moves`](Self::extra_turn_moves) is non-empty.
+    pub fn can_extend_turn(&self) -> bool {
+        self.legal_pits().any(|m| self.move_gives_extra_turn(m))
+    }
+
+    /// The legal moves that grant another move, in ascending pit order.
+    pub fn extra_turn_moves(&self) -> Vec<usize> {
+        self.legal_moves()
+            .into_iter()
+            .filter(|&m| self.move_gives_extra_turn(m))
             .collect()
     }
 
@@ -65,40 +345,151 @@ impl State {
         out
     }
 
+    /// [`legal_actions`](Self::legal_actions) into `buf`, replacing its
+    /// contents and reusing its allocation.
+    pub fn legal_actions_into(&self, buf: &mut Vec<State>) {
+        buf.clear();
+        // safety: every pit is legal by construction
+        buf.extend(self.legal_pits().map(|m| self.child_after_move(m).unwrap()));
+    }
+
+    /// `(pit, successor)` pairs for all legal moves, in ascending pit order.
+    pub fn legal_transitions(&self) -> Vec<(usize, State)> {
+        self.legal_moves()
+            .into_iter()
+            // safety: m is legal by construction
+            .map(|m| (m, self.child_after_move(m).unwrap()))
+            .collect()
+    }
+
+    /// The pit whose move turns `prev` into `self`, or `None` if `self` is not
+    /// a direct legal successor of `prev`. Same as [`infer_move`].
+    pub fn is_successor_of(&self, prev: &State) -> Option<usize> {
+        infer_move(prev, self)
+    }
+
     /// Next state after applying `pit_index` if legal; otherwise `None`.
     pub fn child_after_move(&self, pit_index: usize) -> Option<State> {
-        if self.is_terminal() || pit_index >= PITS_PER_SIDE {
+        self.try_child_after_move(pit_index).ok()
+    }
+
+    /// Apply a move given as `viewer`'s `view_pit`-th pit counting from
+    /// their left, seated on their own side. Each side's pits are numbered
+    /// left to right from its owner's seat, toward their store, so this is
+    /// the internal index; `None` unless `viewer` is to move and the move
+    /// is legal.
+    pub fn apply_from_view(&self, viewer: Player, view_pit: usize) -> Option<State> {
+        if viewer != self.to_move {
             return None;
         }
+        self.child_after_move(view_pit)
+    }
+
+    /// Next state after applying `pit_index`, or why the move is rejected.
+    pub fn try_child_after_move(&self, pit_index: usize) -> Result<State, Error> {
+        if self.is_terminal() {
+            return Err(Error::GameOver);
+        }
+        if pit_index >= PITS_PER_SIDE {
+            return Err(Error::OutOfBounds);
+        }
         let side = self.to_move.idx();
-        if self.pits[side][pit_index] == 0 {
-            return None;
+        if self.pits[side][pit_index] == 0
+            || (self.awaiting_feed() && !self.feeds(pit_index))
+            || (self.rules.grand_slam == GrandSlam::Forbidden && self.move_grand_slams(pit_index))
+        {
+            return Err(Error::IllegalMove);
+        }
+        let mut s = self.clone();
+        s.play(pit_index);
+        debug_assert!(self.kept_turn_is_valid(pit_index, &s));
+        Ok(s)
+    }
+
+    /// Pick a uniformly random legal move and apply it.
+    /// Returns the chosen pit and its successor, or `None` if terminal.
+    pub fn apply_random_move(&self, rng: &mut impl Rng) -> Option<(usize, State)> {
+        let moves = self.legal_moves();
+        let &m = moves.choose(rng)?;
+        // safety: m is legal by construction
+        Some((m, self.child_after_move(m).unwrap()))
+    }
+
+    /// Play `pick`'s move from each position until the game ends, returning
+    /// the final position. Panics if `pick` returns an illegal move.
+    pub fn play_out_with(&self, mut pick: impl FnMut(&State) -> usize) -> State {
+        let mut s = self.clone();
+        while !s.is_terminal() {
+            let m = pick(&s);
+            s = match s.try_child_after_move(m) {
+                Ok(next) => next,
+                Err(e) => panic!("scripted move {m} in {s}: {e}"),
+            };
+        }
+        s
+    }
+
+    /// A legal pit drawn with probability proportional to `weights[pit]`.
+    /// Illegal pits, missing entries and negative weights count as zero; if
+    /// no legal pit has any weight the draw is uniform. `None` when there is
+    /// no legal move.
+    pub fn sample_move_weighted(&self, weights: &[f32], rng: &mut impl Rng) -> Option<usize> {
+        let moves = self.legal_moves();
+        let weight = |&m: &usize| weights.get(m).map_or(0.0, |w| w.max(0.0));
+        match moves.choose_weighted(rng, weight) {
+            Ok(&m) => Some(m),
+            Err(_) => moves.choose(rng).copied(),
         }
-        let mut s = State {
-            pits: self.pits,
-            stores: self.stores,
-            to_move: self.to_move,
-        };
-        s.sow_from_pit(pit_index);
-        Some(s)
     }
 
     /// Terminal if either side has no stones in small pits (after a move,
-    /// remaining stones are swept to stores).
+    /// remaining stones are swept to stores), unless the mover still has to
+    /// feed an empty opponent under [`Rules::feeding_required`].
+    ///
+    /// Sowing only adds to the mover's pits and a capture clears just the
+    /// pit the last stone landed in, so a move can leave its own side empty
+    /// only when it was the mover's last non-empty pit, i.e. the only move.
     pub fn is_terminal(&self) -> bool {
-        self.pits[0].iter().all(|&x| x == 0) || self.pits[1].iter().all(|&x| x == 0)
+        let a_empty = self.pits[0].iter().all(|&x| x == 0);
+        let b_empty = self.pits[1].iter().all(|&x| x == 0);
+        (a_empty || b_empty) && !self.awaiting_feed()
     }
 
-    /// Game outcome, assuming `is_terminal()` is true.
+    /// Game outcome: decided by the stores once the game is over, either
+    /// normally ([`is_terminal`](Self::is_terminal)) or by
+    /// [`stalemate`](Self::is_stalemate); otherwise `Ongoing`.
     pub fn outcome(&self) -> Outcome {
-        if !self.is_terminal() {
+        if !self.is_terminal() && !self.is_stalemate() {
             return Outcome::Ongoing;
         }
-        match self.stores[0].cmp(&self.stores[1]) {
-            Ordering::Greater => Outcome::Win(Player::A),
-            Ordering::Less => Outcome::Win(Player::B),
-            Ordering::Equal => Outcome::Draw,
+        self.leader().map_or(Outcome::Draw, Outcome::Win)
+    }
+
+    /// The game is not over by the sweep rule, yet the side to move has no
+    /// legal move. Such a game ends where it stands, scored on the stores
+    /// without a sweep.
+    ///
+    /// Only [`GrandSlam::Forbidden`] can produce this, when every move
+    /// would capture the opponent's whole side. Otherwise a side with stones
+    /// always has a move, an empty side ends the game, and feeding only
+    /// postpones the end while a feeding move exists.
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_terminal() && self.legal_pits().next().is_none()
+    }
+
+    /// Winner, stores and margin of a finished game; `None` while it is
+    /// still going. Attach the move count with [`GameSummary::with_moves`].
+    pub fn game_summary(&self) -> Option<GameSummary> {
+        if !self.is_terminal() {
+            return None;
         }
+        let (a, b) = self.stores();
+        Some(GameSummary {
+            winner: self.leader(),
+            stores: (a, b),
+            margin: a.abs_diff(b),
+            moves: None,
+        })
     }
 
     /// Store-score difference from `player`'s perspective.
@@ -108,21 +499,315 @@ impl State {
         a - b
     }
 
+    /// Stones on the board, pits and stores together.
+    pub fn total_stones(&self) -> u16 {
+        self.pits
+            .iter()
+            .flatten()
+            .chain(self.stores.iter())
+            .map(|&x| x as u16)
+            .sum()
+    }
+
+    /// The outcome if it can no longer change: the game is over, or one
+    /// store holds more than half of all stones. `None` while still open,
+    /// and always before the end under an extra-turn bonus, which adds
+    /// stones.
+    pub fn is_decided(&self) -> Option<Outcome> {
+        if self.is_terminal() {
+            return Some(self.outcome());
+        }
+        if self.rules.extra_turn_bonus > 0 {
+            return None;
+        }
+        let total = self.total_stones();
+        Player::both()
+            .find(|&p| self.stores[p.idx()] as u16 * 2 > total)
+            .map(Outcome::Win)
+    }
+
+    /// Most stones `player`'s store could end with: everything not already
+    /// in the opponent's store (saturating at `u8::MAX`). Stones added later
+    /// by an extra-turn bonus are not counted.
+    pub fn max_possible_store(&self, player: Player) -> u8 {
+        let rest = self.total_stones() - self.stores[player.opponent().idx()] as u16;
+        rest.min(u8::MAX as u16) as u8
+    }
+
+    /// Fewest stones in any of `side`'s pits.
+    pub fn min_pit_count(&self, side: Player) -> u8 {
+        self.pits[side.idx()].iter().copied().min().unwrap_or(0)
+    }
+
+    /// Non-empty pits on `side`: the moves it would have on its turn, feeding
+    /// restrictions aside.
+    pub fn mobility(&self, side: Player) -> u8 {
+        self.pits[side.idx()].iter().filter(|&&x| x > 0).count() as u8
+    }
+
+    /// Stone-weighted mean pit index of `side`: `0.0` with everything in the
+    /// pit farthest from its store, `PITS_PER_SIDE - 1` with everything next
+    /// to it. `0.0` for an empty side.
+    pub fn centroid(&self, side: Player) -> f32 {
+        let pits = &self.pits[side.idx()];
+        let stones: u32 = pits.iter().map(|&x| x as u32).sum();
+        if stones == 0 {
+            return 0.0;
+        }
+        let moment: u32 = pits
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| i as u32 * x as u32)
+            .sum();
+        moment as f32 / stones as f32
+    }
+
+    /// Lower bound on plies before the side to move could have emptied its
+    /// pits; [`min_plies_to_empty`](Self::min_plies_to_empty) for that side.
+    pub fn moves_to_empty_estimate(&self) -> u32 {
+        self.min_plies_to_empty(self.to_move)
+    }
+
+    /// Lower bound on plies, whoever plays them, before `side`'s pits are
+    /// empty through play: its non-empty pit count.
+    ///
+    /// Each ply empties at most one of `side`'s pits. Its own move empties
+    /// the pit it starts from and only adds to the rest (a capture clears a
+    /// pit that was empty before that move); an opponent's move captures at
+    /// most one pit. The end-of-game sweep, which clears both sides at once
+    /// when either runs out, is not counted.
+    pub fn min_plies_to_empty(&self, side: Player) -> u32 {
+        self.mobility(side) as u32
+    }
+
+    /// The same position with the sides swapped: B's pits and store become
+    /// A's and the other side moves. Pit indices keep their meaning, so move
+    /// `i` here corresponds to move `i` in the mirror.
+    pub fn mirror(&self) -> State {
+        let mut m = Self::from_parts(
+            [self.pits[1], self.pits[0]],
+            [self.stores[1], self.stores[0]],
+            self.to_move.opponent(),
+        );
+        m.set_rules(self.rules);
+        m
+    }
+
+    /// `side`'s pits in reverse index order, everything else unchanged.
+    ///
+    /// Not a rules transform: the result is generally not reachable from
+    /// this position or equivalent to it. Meant for building test positions
+    /// and checking index-sensitive code such as captures and display.
+    pub fn reverse_side(&self, side: Player) -> State {
+        let mut r = self.clone();
+        for (i, &stones) in self.pits(side).iter().rev().enumerate() {
+            r.set_pit(side.idx(), i, stones);
+        }
+        r
+    }
+
+    /// The pie rule: after A's single opening move, B may take over A's
+    /// position instead of replying. Seats keep their labels, so B now owns
+    /// the side A just moved from (and its store) and A, holding the side B
+    /// had, moves next; on the board this is the [`mirror`](Self::mirror).
+    ///
+    /// `None` unless this is the position right after an opening move that
+    /// handed the turn to B.
+    pub fn apply_pie_swap(&self) -> Option<State> {
+        let opening = Self::with_rules(self.rules);
+        if self.to_move != Player::B || self.is_successor_of(&opening).is_none() {
+            return None;
+        }
+        Some(self.mirror())
+    }
+
+    /// [`score_for`](Self::score_for) after [`preview_sweep`](Self::preview_sweep):
+    /// stones on each side counted as their owner's. Exact on terminal
+    /// positions.
+    pub fn projected_score_for(&self, player: Player) -> i32 {
+        let (a, b) = self.preview_sweep();
+        let diff = a as i32 - b as i32;
+        match player {
+            Player::A => diff,
+            Player::B => -diff,
+        }
+    }
+
+    /// Each legal move with the change in the mover's projected score it
+    /// brings. Sowing onto the opponent's side costs two per stone, a
+    /// capture gains two per captured stone.
+    pub fn move_scores(&self) -> Vec<(usize, i32)> {
+        let me = self.to_move;
+        let base = self.projected_score_for(me);
+        self.legal_transitions()
+            .into_iter()
+            .map(|(m, child)| (m, child.projected_score_for(me) - base))
+            .collect()
+    }
+
+    /// The moves of one turn (chained through extra turns) that put the most
+    /// stones into the mover's store before the turn passes or the game
+    /// ends. Ties go to the sequence found first, in ascending pit order.
+    /// Empty on a finished game.
+    pub fn best_turn_sequence(&self) -> Vec<usize> {
+        let mut seq = self.best_turn_from().1;
+        seq.reverse();
+        seq
+    }
+
+    /// Best store gain for the mover within this turn, with its moves in
+    /// reverse order.
+    fn best_turn_from(&self) -> (u16, Vec<usize>) {
+        let me = self.to_move;
+        let mut best: Option<(u16, Vec<usize>)> = None;
+        for (m, child) in self.legal_transitions() {
+            let mut gain = (child.store(me) - self.store(me)) as u16;
+            let mut seq = Vec::new();
+            if child.side_to_move() == Some(me) {
+                let (more, rest) = child.best_turn_from();
+                gain += more;
+                seq = rest;
+            }
+            seq.push(m);
+            if best.as_ref().is_none_or(|(g, _)| gain > *g) {
+                best = Some((gain, seq));
+            }
+        }
+        best.unwrap_or_default()
+    }
+
+    /// Every way the mover can play out this turn, chaining extra turns,
+    /// with the position where it ends: the opponent to move, the game over
+    /// or no move left. Sequences are in ascending pit order, depth first.
+    /// Empty on a finished game.
+    pub fn super_moves(&self) -> Vec<(Vec<usize>, State)> {
+        let mut out = Vec::new();
+        self.super_moves_into(&mut Vec::new(), &mut out);
+        out
+    }
+
+    fn super_moves_into(&self, prefix: &mut Vec<usize>, out: &mut Vec<(Vec<usize>, State)>) {
+        for (m, child) in self.legal_transitions() {
+            prefix.push(m);
+            if child.side_to_move() == Some(self.to_move) && !child.is_stalemate() {
+                child.super_moves_into(prefix, out);
+            } else {
+                out.push((prefix.clone(), child));
+            }
+            prefix.pop();
+        }
+    }
+
+    /// Check that the position is one the engine can play from consistently.
+    pub fn validate(&self) -> Result<(), InvalidState> {
+        let total = self.total_stones();
+        if total > u8::MAX as u16 {
+            return Err(InvalidState::TooManyStones(total));
+        }
+        let a_empty = self.pits[0].iter().all(|&x| x == 0);
+        let b_empty = self.pits[1].iter().all(|&x| x == 0);
+        if a_empty != b_empty && !self.awaiting_feed() {
+            return Err(InvalidState::UnsweptTerminal);
+        }
+        Ok(())
+    }
+
+    /// Zobrist hash of the position (O(1); maintained incrementally).
+    #[inline]
+    pub fn zobrist_hash(&self) -> u64 {
+        debug_assert_eq!(self.hash, self.compute_hash());
+        self.hash
+    }
+
+    /// Zobrist hash recomputed from scratch.
+    fn compute_hash(&self) -> u64 {
+        let mut h = side_key(self.to_move);
+        for side in 0..2 {
+            for idx in 0..PITS_PER_SIDE {
+                h ^= pit_key(side, idx, self.pits[side][idx]);
+            }
+            h ^= store_key(side, self.stores[side]);
+        }
+        h
+    }
+
     // ===== Internal engine =====
 
-    fn sow_from_pit(&mut self, pit_index: usize) {
-        let mover = self.to_move;
-        let mover_i = mover.idx();
+    /// Sowing from `pit` on the mover's side reaches the opponent's pits.
+    #[inline]
+    fn feeds(&self, pit: usize) -> bool {
+        // pit + 1 .. end, then the store, then the opponent's pits
+        self.pits[self.to_move.idx()][pit] as usize > PITS_PER_SIDE - pit
+    }
 
-        let mut stones = self.pits[mover_i][pit_index];
-        debug_assert!(stones > 0);
-        self.pits[mover_i][pit_index] = 0;
+    /// Feeding is on, the opponent's side is empty and the mover can reach it.
+    fn awaiting_feed(&self) -> bool {
+        self.rules.feeding_required
+            && self.pits[self.to_move.opponent().idx()]
+                .iter()
+                .all(|&x| x == 0)
+            && (0..PITS_PER_SIDE).any(|i| self.feeds(i))
+    }
+
+    #[inline]
+    fn set_pit(&mut self, side: usize, idx: usize, stones: u8) {
+        self.hash ^= pit_key(side, idx, self.pits[side][idx]) ^ pit_key(side, idx, stones);
+        self.pits[side][idx] = stones;
+    }
+
+    #[inline]
+    fn set_store(&mut self, side: usize, stones: u8) {
+        self.hash ^= store_key(side, self.stores[side]) ^ store_key(side, stones);
+        self.stores[side] = stones;
+    }
+
+    #[inline]
+    fn set_to_move(&mut self, to_move: Player) {
+        self.hash ^= side_key(self.to_move) ^ side_key(to_move);
+        self.to_move = to_move;
+    }
+
+    /// Drop `stones` one by one after the mover's `pit_index`, returning
+    /// where the last one landed. Sowing that stays on the mover's side
+    /// (the common case) skips the step-by-step walk.
+    fn sow(&mut self, pit_index: usize, stones: u8, emit: &mut impl FnMut(MoveEvent)) -> Loc {
+        if stones as usize <= PITS_PER_SIDE - pit_index {
+            self.sow_short(pit_index, stones, emit)
+        } else {
+            self.sow_loop(pit_index, stones, emit)
+        }
+    }
 
-        #[derive(Copy, Clone)]
-        enum Loc {
-            Pit { side: Player, idx: usize },
-            Store { side: Player },
+    fn sow_short(&mut self, pit_index: usize, stones: u8, emit: &mut impl FnMut(MoveEvent)) -> Loc {
+        let mover = self.to_move;
+        let i = mover.idx();
+        let end = pit_index + stones as usize;
+        for idx in pit_index + 1..=end.min(PITS_PER_SIDE - 1) {
+            self.set_pit(i, idx, self.pits[i][idx] + 1);
+            emit(MoveEvent::Sow {
+                loc: Loc::Pit { side: mover, idx },
+            });
+        }
+        if end == PITS_PER_SIDE {
+            self.set_store(i, self.stores[i] + 1);
+            let loc = Loc::Store { side: mover };
+            emit(MoveEvent::Sow { loc });
+            loc
+        } else {
+            Loc::Pit {
+                side: mover,
+                idx: end,
+            }
         }
+    }
+
+    fn sow_loop(
+        &mut self,
+        pit_index: usize,
+        mut stones: u8,
+        emit: &mut impl FnMut(MoveEvent),
+    ) -> Loc {
+        let mover = self.to_move;
 
         #[inline]
         fn next(loc: Loc) -> Loc {
@@ -155,53 +840,195 @@ impl State {
             }
 
             match loc {
-                Loc::Pit { side, idx } => self.pits[side.idx()][idx] += 1,
-                Loc::Store { side } => self.stores[side.idx()] += 1,
+                Loc::Pit { side, idx } => {
+                    let i = side.idx();
+                    self.set_pit(i, idx, self.pits[i][idx] + 1);
+                }
+                Loc::Store { side } => {
+                    let i = side.idx();
+                    self.set_store(i, self.stores[i] + 1);
+                }
             }
+            emit(MoveEvent::Sow { loc });
 
             stones -= 1;
             last = loc;
         }
 
+        last
+    }
+
+    /// Play `pit_index` in place, reporting each step to `emit`.
+    pub(crate) fn sow_from_pit(&mut self, pit_index: usize, mut emit: impl FnMut(MoveEvent)) {
+        let mover = self.to_move;
+        let mover_i = mover.idx();
+
+        let stones = self.pits[mover_i][pit_index];
+        debug_assert!(stones > 0);
+        self.set_pit(mover_i, pit_index, 0);
+        let last = self.sow(pit_index, stones, &mut emit);
+        self.finish_move(last, &mut emit);
+        self.last_move = Some(pit_index);
+    }
+
+    /// [`sow_from_pit`](Self::sow_from_pit) without events, using the
+    /// precomputed table when the rules allow.
+    fn play(&mut self, pit_index: usize) {
+        let mover_i = self.to_move.idx();
+        let stones = self.pits[mover_i][pit_index];
+        debug_assert!(stones > 0);
+        self.set_pit(mover_i, pit_index, 0);
+        let last = match sow_table::lookup(self.rules, pit_index, stones) {
+            Some(sowing) => self.sow_tabled(sowing),
+            None => self.sow(pit_index, stones, &mut |_| {}),
+        };
+        self.finish_move(last, &mut |_| {});
+        self.last_move = Some(pit_index);
+    }
+
+    fn sow_tabled(&mut self, sowing: &Sowing) -> Loc {
+        let mover = self.to_move;
+        let (me, opp) = (mover.idx(), mover.opponent().idx());
+        for idx in 0..PITS_PER_SIDE {
+            let (own, theirs) = (sowing.deltas[idx], sowing.deltas[PITS_PER_SIDE + 1 + idx]);
+            if own > 0 {
+                self.set_pit(me, idx, self.pits[me][idx] + own);
+            }
+            if theirs > 0 {
+                self.set_pit(opp, idx, self.pits[opp][idx] + theirs);
+            }
+        }
+        let store = sowing.deltas[PITS_PER_SIDE];
+        if store > 0 {
+            self.set_store(me, self.stores[me] + store);
+        }
+        match sowing.last {
+            idx if idx < PITS_PER_SIDE => Loc::Pit { side: mover, idx },
+            PITS_PER_SIDE => Loc::Store { side: mover },
+            slot => {
+                debug_assert!(slot < LAP);
+                Loc::Pit {
+                    side: mover.opponent(),
+                    idx: slot - PITS_PER_SIDE - 1,
+                }
+            }
+        }
+    }
+
+    /// Capture, turn change and end-of-game sweep after the last stone
+    /// landed at `last`.
+    fn finish_move(&mut self, last: Loc, emit: &mut impl FnMut(MoveEvent)) {
+        let mover = self.to_move;
+        let mover_i = mover.idx();
+
+        let mut extra = false;
+
         // capture: last stone landed on mover's empty pit; take opposite as well
+        let skip_slam = self.rules.grand_slam == GrandSlam::NoCapture && self.grand_slam_at(last);
         if let Loc::Pit { side, idx } = last
             && side == mover
             && self.pits[mover_i][idx] == 1
+            && !skip_slam
         {
-            let opp = mover.opponent();
+            let (opp, opp_idx) = Self::capture_partner(mover, idx);
             let opp_i = opp.idx();
-            let opp_idx = PITS_PER_SIDE - 1 - idx;
             let captured = self.pits[opp_i][opp_idx];
             if captured > 0 {
-                self.pits[mover_i][idx] = 0;
-                self.pits[opp_i][opp_idx] = 0;
-                self.stores[mover_i] += captured + 1;
+                self.set_pit(mover_i, idx, 0);
+                self.set_pit(opp_i, opp_idx, 0);
+                self.set_store(mover_i, self.stores[mover_i] + captured + 1);
+                self.captured[mover_i] = self.captured[mover_i].saturating_add(captured as u16);
+                emit(MoveEvent::Capture {
+                    pit: idx,
+                    stones: captured,
+                    from: opp,
+                });
+                extra = self.rules.capture_grants_extra_turn;
             }
         }
 
-        // extra turn if last stone in mover's store; otherwise flip turn
-        let extra = matches!(last, Loc::Store { side } if side == mover);
-        if !extra {
-            self.to_move = mover.opponent();
+        // extra turn if last stone in mover's store (or a rewarded capture);
+        // otherwise flip turn
+        if matches!(last, Loc::Store { side } if side == mover) {
+            extra = true;
+            // capped so the stone total stays within a u8 cell
+            let room = u8::MAX as u16 - self.total_stones().min(u8::MAX as u16);
+            let bonus = (self.rules.extra_turn_bonus as u16).min(room) as u8;
+            if bonus > 0 {
+                self.set_store(mover_i, self.stores[mover_i] + bonus);
+            }
+        }
+        if extra {
+            emit(MoveEvent::ExtraTurn);
+        } else {
+            self.set_to_move(mover.opponent());
         }
 
-        // end-of-game sweep if any side is empty
+        // end-of-game sweep if any side is empty (and no feeding is owed)
         let player_a_empty = self.pits[Player::A.idx()].iter().all(|&x| x == 0);
         let player_b_empty = self.pits[Player::B.idx()].iter().all(|&x| x == 0);
-        if player_a_empty || player_b_empty {
-            for i in 0..PITS_PER_SIDE {
-                self.stores[0] += self.pits[0][i];
-                self.pits[0][i] = 0;
-                self.stores[1] += self.pits[1][i];
-                self.pits[1][i] = 0;
+        if (player_a_empty || player_b_empty) && !self.awaiting_feed() {
+            for p in Player::both() {
+                let side = p.idx();
+                let swept: u8 = self.pits[side].iter().sum();
+                if swept == 0 {
+                    continue;
+                }
+                for i in 0..PITS_PER_SIDE {
+                    self.set_pit(side, i, 0);
+                }
+                self.set_store(side, self.stores[side] + swept);
+                emit(MoveEvent::Sweep {
+                    side: p,
+                    stones: swept,
+                });
+            }
+        }
+    }
+}
+
+/// The legal pit from `prev` whose move produces `next`, for replaying a
+/// game received as a sequence of positions. `None` if no move does, or if
+/// more than one does (which distinct pits never do under the current
+/// rules).
+pub fn infer_move(prev: &State, next: &State) -> Option<usize> {
+    let mut found = None;
+    for (m, s) in prev.legal_transitions() {
+        if s == *next {
+            if found.is_some() {
+                return None;
             }
+            found = Some(m);
         }
     }
+    found
+}
+
+/// Outcome of playing uniformly random moves from `state` for at most
+/// `max_len` plies; [`Outcome::Ongoing`] if the game has not ended by then.
+pub fn random_playout(state: &State, rng: &mut impl Rng, max_len: usize) -> Outcome {
+    random_playout_len(state, rng, max_len).0
+}
+
+/// [`random_playout`] with the number of plies actually played.
+pub fn random_playout_len(state: &State, rng: &mut impl Rng, max_len: usize) -> (Outcome, usize) {
+    let mut s = state.clone();
+    let mut plies = 0;
+    while plies < max_len {
+        let Some((_, next)) = s.apply_random_move(rng) else {
+            break;
+        };
+        s = next;
+        plies += 1;
+    }
+    (s.outcome(), plies)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
 
     fn total(st: &State) -> u16 {
         let a: u16 = st.pits(Player::A).iter().map(|&x| x as u16).sum();
@@ -216,17 +1043,590 @@ mod tests {
     }
 
     #[test]
-    fn extra_turn_happens_on_initial_from_pit_2() {
-        assert_eq!(PITS_PER_SIDE, 6);
-        assert_eq!(STONES_PER_PIT, 4);
-        let s = State::new();
-        let me = s.current_player();
-        let child = s.child_after_move(2).unwrap();
-        assert_eq!(child.current_player(), me);
+    fn emptying_own_side_is_always_the_only_move() {
+        let variants = [
+            Rules::default(),
+            Rules {
+                feeding_required: true,
+                ..Default::default()
+            },
+            Rules {
+                capture_grants_extra_turn: true,
+                ..Default::default()
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(21);
+        for rules in variants {
+            for _ in 0..300 {
+                let mut s = State::with_rules(rules);
+                while !s.is_terminal() {
+                    let mover = s.current_player();
+                    let legal = s.legal_moves();
+                    for &m in &legal {
+                        let (next, events) = s.apply_move_events(m).unwrap();
+                        let swept_mover = events
+                            .iter()
+                            .any(|e| matches!(e, MoveEvent::Sweep { side, .. } if *side == mover));
+                        if next.is_terminal() && !swept_mover {
+                            assert_eq!(legal, [m], "{s}");
+                        }
+                    }
+                    s = s.apply_random_move(&mut rng).unwrap().1;
+                }
+            }
+        }
     }
 
     #[test]
-    fn turn_switches_when_not_ending_in_own_store() {
+    fn pie_swap_after_opening_move() {
+        let start = State::new();
+        let after = start.child_after_move(0).unwrap();
+        let swapped = after.apply_pie_swap().unwrap();
+        assert_eq!(swapped.current_player(), Player::A);
+        assert_eq!(swapped.pits(Player::B), after.pits(Player::A));
+        assert_eq!(swapped.pits(Player::A), after.pits(Player::B));
+        assert_eq!(swapped.stores(), (0, 0));
+
+        // pit 2 keeps A on move, so there is nothing to swap yet
+        assert_eq!(start.child_after_move(2).unwrap().apply_pie_swap(), None);
+        assert_eq!(start.apply_pie_swap(), None);
+        let reply = after.child_after_move(1).unwrap();
+        assert_eq!(reply.apply_pie_swap(), None);
+    }
+
+    #[test]
+    fn game_summary_of_finished_game() {
+        let mut s = State::new();
+        assert_eq!(s.game_summary(), None);
+        let mut history = Vec::new();
+        while let Some(&m) = s.legal_moves().first() {
+            history.push(m);
+            s = s.child_after_move(m).unwrap();
+        }
+        let summary = s.game_summary().unwrap().with_moves(history.len());
+        assert_eq!(summary.moves, Some(history.len()));
+        let expected = match s.outcome() {
+            Outcome::Win(p) => Some(p),
+            _ => None,
+        };
+        assert_eq!(summary.winner, expected);
+        assert_eq!(summary.stores, s.stores());
+        assert_eq!(summary.margin as i32, s.score_for(Player::A).abs());
+    }
+
+    #[test]
+    fn sample_move_weighted_respects_weights() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let s = State::new();
+        let mut counts = [0u32; PITS_PER_SIDE];
+        for _ in 0..6000 {
+            counts[s
+                .sample_move_weighted(&[1.0; PITS_PER_SIDE], &mut rng)
+                .unwrap()] += 1;
+        }
+        assert!(
+            counts.iter().all(|&c| (800..1200).contains(&c)),
+            "{counts:?}"
+        );
+
+        let mut weights = [0.0; PITS_PER_SIDE];
+        weights[1] = 1.0;
+        weights[4] = 3.0;
+        for _ in 0..200 {
+            let m = s.sample_move_weighted(&weights, &mut rng).unwrap();
+            assert!(m == 1 || m == 4);
+        }
+        // nothing weighted: fall back to any legal move
+        let m = s.sample_move_weighted(&[], &mut rng).unwrap();
+        assert!(s.legal_moves().contains(&m));
+    }
+
+    #[test]
+    fn reverse_side_is_an_involution() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut s = State::new();
+        for _ in 0..7 {
+            s = s.apply_random_move(&mut rng).unwrap().1;
+        }
+        for side in Player::both() {
+            let r = s.reverse_side(side);
+            assert_eq!(r.total_stones(), s.total_stones());
+            assert_eq!(r.pits(side)[0], s.pits(side)[PITS_PER_SIDE - 1]);
+            assert_eq!(r.pits(side.opponent()), s.pits(side.opponent()));
+            assert_eq!(r.reverse_side(side), s);
+            assert_eq!(r.reverse_side(side).zobrist_hash(), s.zobrist_hash());
+        }
+    }
+
+    #[test]
+    fn last_move_is_reported_but_not_compared() {
+        let start = State::new();
+        assert_eq!(start.last_move(), None);
+        let after = start.child_after_move(4).unwrap();
+        assert_eq!(after.last_move(), Some(4));
+        assert_eq!(after.clone().last_move(), Some(4));
+        assert_eq!(start.apply_move_events(4).unwrap().0.last_move(), Some(4));
+
+        let set_up = State::from_parts(
+            [*after.pits(Player::A), *after.pits(Player::B)],
+            [after.store(Player::A), after.store(Player::B)],
+            after.current_player(),
+        );
+        assert_eq!(set_up.last_move(), None);
+        assert_eq!(set_up, after);
+        let mut seen = std::collections::HashSet::new();
+        seen.insert(after);
+        assert!(seen.contains(&set_up));
+    }
+
+    #[test]
+    fn centroid_tracks_distance_from_store() {
+        let near = PITS_PER_SIDE - 1;
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[0][near] = 7;
+        pits[1][0] = 7;
+        let s = State::from_parts(pits, [0, 0], Player::A);
+        assert_eq!(s.centroid(Player::A), near as f32);
+        assert_eq!(s.centroid(Player::B), 0.0);
+
+        let start = State::new();
+        assert_eq!(start.centroid(Player::A), near as f32 / 2.0);
+        let empty = State::from_parts([[0; PITS_PER_SIDE], [1; PITS_PER_SIDE]], [0, 0], Player::B);
+        assert_eq!(empty.centroid(Player::A), 0.0);
+    }
+
+    #[test]
+    fn min_plies_to_empty_is_a_sound_bound() {
+        // B's last stone sits opposite A's empty pit 1: A captures it at once
+        let lone = State::from_parts(
+            [[1, 0, 2, 0, 0, 0], [0, 0, 0, 0, 1, 0]],
+            [20, 24],
+            Player::A,
+        );
+        assert_eq!(lone.min_plies_to_empty(Player::B), 1);
+        let after = lone.child_after_move(0).unwrap();
+        assert!(after.is_terminal());
+
+        // no ply empties more than one pit before the sweep
+        let mut rng = StdRng::seed_from_u64(8);
+        for _ in 0..100 {
+            let mut s = State::new();
+            while !s.is_terminal() {
+                for m in s.legal_moves() {
+                    let (next, events) = s.apply_move_events(m).unwrap();
+                    for side in Player::both() {
+                        let swept = events
+                            .iter()
+                            .any(|e| matches!(e, MoveEvent::Sweep { side: p, .. } if *p == side));
+                        // a swept side was not empty; an unswept terminal one was
+                        let left = match (next.is_terminal(), swept) {
+                            (true, true) => continue,
+                            (true, false) => 0,
+                            (false, _) => next.min_plies_to_empty(side),
+                        };
+                        assert!(s.min_plies_to_empty(side) <= left + 1, "{s} / {m}");
+                    }
+                }
+                s = s.apply_random_move(&mut rng).unwrap().1;
+            }
+        }
+    }
+
+    #[test]
+    fn play_out_with_lowest_legal_pit() {
+        let start = State::new();
+        let mut plies = 0;
+        let end = start.play_out_with(|s| {
+            assert_eq!(s.total_stones(), start.total_stones());
+            plies += 1;
+            s.legal_moves()[0]
+        });
+        assert!(end.is_terminal());
+        assert_eq!(end.total_stones(), start.total_stones());
+        assert!(plies > 0);
+        // deterministic: the same script reaches the same end
+        assert_eq!(start.play_out_with(|s| s.legal_moves()[0]), end);
+    }
+
+    #[test]
+    fn capture_partner_is_an_involution() {
+        for side in Player::both() {
+            for pit in 0..PITS_PER_SIDE {
+                let (other, across) = State::capture_partner(side, pit);
+                assert_eq!(other, side.opponent());
+                assert_eq!(State::capture_partner(other, across), (side, pit));
+            }
+        }
+        assert_eq!(
+            State::capture_partner(Player::A, 0),
+            (Player::B, PITS_PER_SIDE - 1)
+        );
+    }
+
+    #[test]
+    fn no_stalemate_under_current_rules() {
+        let variants = [
+            Rules::default(),
+            Rules {
+                feeding_required: true,
+                ..Default::default()
+            },
+            Rules {
+                capture_grants_extra_turn: true,
+                extra_turn_bonus: 1,
+                ..Default::default()
+            },
+        ];
+        let mut rng = StdRng::seed_from_u64(13);
+        for rules in variants {
+            for _ in 0..100 {
+                let mut s = State::with_rules(rules);
+                loop {
+                    assert!(!s.is_stalemate(), "{s}");
+                    match s.apply_random_move(&mut rng) {
+                        Some((_, next)) => s = next,
+                        None => break,
+                    }
+                }
+                assert_ne!(s.outcome(), Outcome::Ongoing);
+            }
+        }
+    }
+
+    #[test]
+    fn grand_slam_modes() {
+        // pit 0 sows into empty pit 1, across from B's only stones
+        let pos = |grand_slam| {
+            let mut s = State::from_parts(
+                [[1, 0, 0, 0, 0, 2], [0, 0, 0, 0, 3, 0]],
+                [20, 22],
+                Player::A,
+            );
+            s.set_rules(Rules {
+                grand_slam,
+                ..Rules::default()
+            });
+            s
+        };
+
+        let allowed = pos(GrandSlam::Allowed);
+        assert_eq!(allowed.legal_moves(), [0, 5]);
+        let next = allowed.child_after_move(0).unwrap();
+        assert_eq!(next.captures(Player::A), 3);
+        assert!(next.is_terminal());
+        assert_eq!(next.stores(), (26, 22));
+
+        let forbidden = pos(GrandSlam::Forbidden);
+        assert_eq!(forbidden.legal_moves(), [5]);
+        assert!(forbidden.child_after_move(0).is_none());
+
+        let no_capture = pos(GrandSlam::NoCapture);
+        assert_eq!(no_capture.legal_moves(), [0, 5]);
+        let next = no_capture.child_after_move(0).unwrap();
+        assert_eq!(next.captures(Player::A), 0);
+        assert_eq!(next.pits(Player::A), &[0, 1, 0, 0, 0, 2]);
+        assert_eq!(next.pits(Player::B), &[0, 0, 0, 0, 3, 0]);
+        assert_eq!(next.current_player(), Player::B);
+
+        // with the only move a grand slam, forbidding it is a stalemate
+        let mut only = pos(GrandSlam::Forbidden);
+        only.set_pit(0, 5, 0);
+        assert!(only.legal_moves().is_empty());
+        assert!(only.is_stalemate());
+    }
+
+    #[test]
+    fn new_with_mover_sets_first_player() {
+        for p in Player::both() {
+            let s = State::new_with_mover(p);
+            assert_eq!(s.current_player(), p);
+            assert_eq!(s.legal_moves().len(), PITS_PER_SIDE);
+        }
+        assert_eq!(State::new_with_mover(Player::A), State::new());
+    }
+
+    #[test]
+    fn leader_follows_store_counts() {
+        let s = State::new();
+        assert_eq!(s.stores(), (0, 0));
+        assert_eq!(s.leader(), None);
+
+        let b_ahead =
+            State::from_parts([[4; PITS_PER_SIDE], [3; PITS_PER_SIDE]], [0, 6], Player::A);
+        assert_eq!(b_ahead.stores(), (0, 6));
+        assert_eq!(b_ahead.leader(), Some(Player::B));
+        assert_eq!(b_ahead.mirror().leader(), Some(Player::A));
+    }
+
+    #[test]
+    fn short_sowing_matches_reference_loop() {
+        let mut rng = StdRng::seed_from_u64(44);
+        let mut boards = vec![State::new()];
+        let mut s = State::new();
+        while let Some((_, next)) = s.apply_random_move(&mut rng) {
+            boards.push(next.clone());
+            s = next;
+        }
+
+        for base in &boards {
+            let me = base.to_move.idx();
+            for pit in 0..PITS_PER_SIDE {
+                // up to three laps of the 2P + 1 sown locations
+                for stones in 1..=(3 * (2 * PITS_PER_SIDE + 1)) as u8 {
+                    let mut fast = base.clone();
+                    fast.set_pit(me, pit, 0);
+                    let mut slow = fast.clone();
+                    let (mut ev_fast, mut ev_slow) = (Vec::new(), Vec::new());
+                    let last_fast = fast.sow(pit, stones, &mut |e| ev_fast.push(e));
+                    let last_slow = slow.sow_loop(pit, stones, &mut |e| ev_slow.push(e));
+                    assert_eq!(last_fast, last_slow, "pit {pit}, {stones} stones");
+                    assert_eq!(ev_fast, ev_slow);
+                    assert_eq!(fast, slow);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn table_sowing_matches_general_engine() {
+        let mut rng = StdRng::seed_from_u64(45);
+        for _ in 0..50 {
+            let mut s = State::new();
+            while !s.is_terminal() {
+                for &m in &s.legal_moves() {
+                    let mut tabled = s.clone();
+                    tabled.play(m);
+                    let mut general = s.clone();
+                    general.sow_from_pit(m, |_| {});
+                    assert_eq!(tabled, general);
+                }
+                s = s.apply_random_move(&mut rng).unwrap().1;
+            }
+        }
+
+        // every count a standard board holds is covered; variants are not
+        let max = (2 * PITS_PER_SIDE) as u8 * STONES_PER_PIT;
+        assert!(sow_table::lookup(Rules::default(), 0, max).is_some());
+        assert!(sow_table::lookup(Rules::default(), 0, max + 1).is_none());
+        let bonus = Rules {
+            extra_turn_bonus: 1,
+            ..Rules::default()
+        };
+        assert!(sow_table::lookup(bonus, 0, 4).is_none());
+    }
+
+    #[test]
+    fn max_possible_store_is_tight_at_the_end() {
+        let s = State::new();
+        for p in Player::both() {
+            assert_eq!(s.max_possible_store(p) as u16, s.total_stones());
+        }
+
+        let mut rng = StdRng::seed_from_u64(46);
+        let mut s = s;
+        while let Some((_, next)) = s.apply_random_move(&mut rng) {
+            for p in Player::both() {
+                assert!(next.max_possible_store(p) <= s.max_possible_store(p));
+                assert!(next.store(p) <= next.max_possible_store(p));
+            }
+            s = next;
+        }
+        for p in Player::both() {
+            assert_eq!(s.max_possible_store(p), s.store(p));
+        }
+    }
+
+    #[test]
+    fn extra_turn_that_ends_the_game_keeps_the_mover() {
+        let mut a = [0; PITS_PER_SIDE];
+        a[PITS_PER_SIDE - 1] = 1;
+        let s = State::from_parts([a, [2; PITS_PER_SIDE]], [20, 13], Player::A);
+        let end = s.child_after_move(PITS_PER_SIDE - 1).unwrap();
+        assert!(end.is_terminal());
+        assert_eq!(end.current_player(), Player::A);
+        assert_eq!(end.side_to_move(), None);
+        assert!(s.kept_turn_is_valid(PITS_PER_SIDE - 1, &end));
+
+        // a last move that overshoots the store hands the turn over
+        let mut b = [0; PITS_PER_SIDE];
+        b[PITS_PER_SIDE - 1] = 2;
+        let s = State::from_parts([[0, 0, 0, 0, 0, 1], b], [20, 25], Player::B);
+        let end = s.child_after_move(PITS_PER_SIDE - 1).unwrap();
+        assert!(end.is_terminal());
+        assert_eq!(end.current_player(), Player::A);
+        assert!(s.kept_turn_is_valid(PITS_PER_SIDE - 1, &end));
+        assert!(!s.kept_turn_is_valid(PITS_PER_SIDE - 1, &s));
+    }
+
+    #[test]
+    fn kept_turn_holds_along_random_games() {
+        let mut rng = StdRng::seed_from_u64(47);
+        for _ in 0..20 {
+            let mut s = State::new();
+            while let Some((m, next)) = s.apply_random_move(&mut rng) {
+                assert!(s.kept_turn_is_valid(m, &next));
+                s = next;
+            }
+        }
+    }
+
+    #[test]
+    fn side_to_move_is_none_once_over() {
+        assert_eq!(State::new().side_to_move(), Some(Player::A));
+        let s = State::new().child_after_move(0).unwrap();
+        assert_eq!(s.side_to_move(), Some(Player::B));
+
+        let over = State::from_parts([[0; PITS_PER_SIDE]; 2], [30, 18], Player::B);
+        assert!(over.is_terminal());
+        assert_eq!(over.side_to_move(), None);
+        assert!(over.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn buffer_variants_match_allocating_ones() {
+        let mut rng = StdRng::seed_from_u64(48);
+        let mut moves = vec![99];
+        let mut actions = vec![State::new()];
+        let mut s = State::new();
+        loop {
+            s.legal_moves_into(&mut moves);
+            s.legal_actions_into(&mut actions);
+            assert_eq!(moves, s.legal_moves());
+            assert_eq!(actions, s.legal_actions());
+            match s.apply_random_move(&mut rng) {
+                Some((_, next)) => s = next,
+                None => break,
+            }
+        }
+        assert!(moves.is_empty() && actions.is_empty());
+    }
+
+    #[test]
+    fn extra_turn_chain_sets_up_a_capture() {
+        // 5 alone banks one stone; after it, 4 lands in the emptied pit 5 and
+        // captures B's pit 0
+        let s = State::from_parts([[1, 0, 0, 0, 1, 1], [5, 0, 1, 0, 0, 0]], [0, 0], Player::A);
+        let single = s
+            .legal_transitions()
+            .into_iter()
+            .map(|(_, c)| c.store(Player::A))
+            .max()
+            .unwrap();
+        assert_eq!(single, 1);
+
+        assert_eq!(s.best_turn_sequence(), vec![5, 4]);
+        let end = s.child_after_move(5).unwrap().child_after_move(4).unwrap();
+        assert_eq!(end.store(Player::A), 7);
+        assert_eq!(end.current_player(), Player::B);
+
+        assert!(
+            State::from_parts([[0; PITS_PER_SIDE]; 2], [24, 24], Player::A)
+                .best_turn_sequence()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn can_extend_turn_matches_extra_turn_moves() {
+        assert!(State::new().can_extend_turn());
+        // every pit overshoots the store without lapping back to it
+        let s = State::from_parts(
+            [[0, 0, 0, 0, 3, 3], [1, 1, 1, 1, 1, 1]],
+            [17, 20],
+            Player::A,
+        );
+        assert!(s.extra_turn_moves().is_empty());
+        assert!(!s.can_extend_turn());
+    }
+
+    #[test]
+    fn super_moves_chain_the_opening_extra_turn() {
+        let s = State::new();
+        let supers = s.super_moves();
+        let seqs: Vec<Vec<usize>> = supers.iter().map(|(seq, _)| seq.clone()).collect();
+        let expected: Vec<Vec<usize>> = [vec![0], vec![1]]
+            .into_iter()
+            .chain([0, 1, 3, 4, 5].map(|m| vec![2, m]))
+            .chain([vec![3], vec![4], vec![5]])
+            .collect();
+        assert_eq!(seqs, expected);
+        for (seq, end) in &supers {
+            let replayed = seq
+                .iter()
+                .fold(s.clone(), |st, &m| st.child_after_move(m).unwrap());
+            assert!(replayed == *end);
+            assert_eq!(end.current_player(), Player::B);
+        }
+
+        let over = State::from_parts([[0; PITS_PER_SIDE]; 2], [24, 24], Player::A);
+        assert!(over.super_moves().is_empty());
+    }
+
+    #[test]
+    fn view_relative_moves_match_direct_ones() {
+        let s = State::new().child_after_move(0).unwrap();
+        let me = s.current_player();
+        for pit in 0..PITS_PER_SIDE {
+            assert_eq!(s.apply_from_view(me, pit), s.child_after_move(pit));
+            assert_eq!(s.apply_from_view(me.opponent(), pit), None);
+        }
+        assert_eq!(s.apply_from_view(me, PITS_PER_SIDE), None);
+    }
+
+    #[test]
+    fn mobility_counts_either_side() {
+        let s = State::new();
+        for p in Player::both() {
+            assert_eq!(s.mobility(p) as usize, PITS_PER_SIDE);
+        }
+        let next = s.child_after_move(0).unwrap();
+        assert_eq!(next.mobility(Player::A) as usize, PITS_PER_SIDE - 1);
+        assert_eq!(next.mobility(Player::B) as usize, PITS_PER_SIDE);
+    }
+
+    #[test]
+    fn extra_turn_happens_on_initial_from_pit_2() {
+        assert_eq!(PITS_PER_SIDE, 6);
+        assert_eq!(STONES_PER_PIT, 4);
+        let s = State::new();
+        let me = s.current_player();
+        let child = s.child_after_move(2).unwrap();
+        assert_eq!(child.current_player(), me);
+    }
+
+    #[test]
+    fn opening_extra_turn_pit_for_each_rule_set() {
+        let variants = [
+            Rules::default(),
+            Rules {
+                extra_turn_bonus: 3,
+                ..Rules::default()
+            },
+            Rules {
+                feeding_required: true,
+                capture_grants_extra_turn: true,
+                ..Rules::default()
+            },
+        ];
+        for rules in variants {
+            let pit = State::extra_turn_pit_on_fresh_board(rules).unwrap();
+            let s = State::with_rules(rules);
+            assert_eq!(pit, PITS_PER_SIDE - STONES_PER_PIT as usize);
+            assert_eq!(
+                s.child_after_move(pit).unwrap().side_to_move(),
+                Some(Player::A)
+            );
+            for other in (0..PITS_PER_SIDE).filter(|&p| p != pit) {
+                assert_eq!(
+                    s.child_after_move(other).unwrap().current_player(),
+                    Player::B
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn turn_switches_when_not_ending_in_own_store() {
         let s = State::new();
         let me = s.current_player();
         let child = s.child_after_move(0).unwrap();
@@ -235,31 +1635,85 @@ mod tests {
 
     #[test]
     fn capture_rule_works() {
-        let mut s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][0] = 1;
-        s.pits[Player::A.idx()][1] = 0;
-        s.pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 3;
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][0] = 1;
+        pits[Player::A.idx()][1] = 0;
+        pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 3;
+        let s = State::from_parts(pits, [0, 0], Player::A);
         let child = s.child_after_move(0).unwrap();
         assert_eq!(child.store(Player::A), 4);
         assert_eq!(child.pits(Player::A)[1], 0);
         assert_eq!(child.pits(Player::B)[PITS_PER_SIDE - 1 - 1], 0);
     }
 
+    #[test]
+    fn safe_moves_avoid_handing_over_a_capture() {
+        // B's pit 0 sows into its empty pit 1, opposite A's pit 4
+        let s = State::from_parts([[2, 0, 0, 1, 0, 1], [1, 0, 0, 0, 0, 3]], [0, 0], Player::A);
+        let walk_in = s.child_after_move(3).unwrap();
+        assert_eq!(walk_in.capturing_moves(), [0]);
+        assert!(s.child_after_move(0).unwrap().capturing_moves().is_empty());
+        // pit 5 keeps the turn
+        assert_eq!(s.safe_moves(), [0, 5]);
+    }
+
+    #[test]
+    fn captures_count_taken_stones() {
+        let s = State::from_parts([[1, 0, 3, 0, 0, 0], [0, 0, 0, 2, 4, 0]], [0, 0], Player::A);
+        let after = s.child_after_move(0).unwrap();
+        assert_eq!(after.captures(Player::A), 4);
+        assert_eq!(after.captures(Player::B), 0);
+        assert_eq!(after.store(Player::A), 5);
+        assert_eq!(s.apply_move_events(0).unwrap().0.captures(Player::A), 4);
+
+        // carried along, and not part of equality
+        let later = after.child_after_move(3).unwrap();
+        assert_eq!(later.captures(Player::A), 4);
+        let fresh = State::from_parts(
+            [*after.pits(Player::A), *after.pits(Player::B)],
+            [after.store(Player::A), after.store(Player::B)],
+            after.current_player(),
+        );
+        assert_eq!(fresh.captures(Player::A), 0);
+        assert_eq!(fresh, after);
+    }
+
+    #[test]
+    fn capture_can_grant_extra_turn() {
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][0] = 1;
+        pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 3;
+        // keep the game going
+        pits[Player::A.idx()][3] = 2;
+        pits[Player::B.idx()][0] = 1;
+        let mut s = State::from_parts(pits, [0, 0], Player::A);
+        assert_eq!(s.child_after_move(0).unwrap().current_player(), Player::B);
+
+        s.set_rules(Rules {
+            capture_grants_extra_turn: true,
+            ..Rules::default()
+        });
+        assert!(s.move_gives_extra_turn(0));
+        let child = s.child_after_move(0).unwrap();
+        assert_eq!(child.store(Player::A), 4);
+        assert_eq!(child.side_to_move(), Some(Player::A));
+
+        // landing in the store still counts
+        let mut t = child.clone();
+        t.set_pit(Player::A.idx(), PITS_PER_SIDE - 1, 1);
+        assert!(t.move_gives_extra_turn(PITS_PER_SIDE - 1));
+        // a plain move does not
+        assert!(!State::with_rules(s.rules()).move_gives_extra_turn(0));
+    }
+
     #[test]
     fn no_capture_when_opposite_empty() {
-        let mut s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][0] = 1;
-        s.pits[Player::A.idx()][1] = 0;
-        s.pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 0;
-        s.pits[Player::B.idx()][0] = 1; // make it non-terminal
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][0] = 1;
+        pits[Player::A.idx()][1] = 0;
+        pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 0;
+        pits[Player::B.idx()][0] = 1; // make it non-terminal
+        let s = State::from_parts(pits, [0, 0], Player::A);
 
         let child = s.child_after_move(0).unwrap();
         assert_eq!(child.store(Player::A), 0);
@@ -268,25 +1722,18 @@ mod tests {
 
     #[test]
     fn child_is_none_on_terminal_position() {
-        let s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [0, 0], Player::A);
         assert!(s.is_terminal());
         assert!(s.child_after_move(0).is_none());
     }
 
     #[test]
     fn no_capture_when_landing_on_non_empty_own_pit() {
-        let mut s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][0] = 2;
-        s.pits[Player::A.idx()][1] = 1;
-        s.pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 5;
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][0] = 2;
+        pits[Player::A.idx()][1] = 1;
+        pits[Player::B.idx()][PITS_PER_SIDE - 1 - 1] = 5;
+        let s = State::from_parts(pits, [0, 0], Player::A);
         let child = s.child_after_move(0).unwrap();
         assert_eq!(child.pits(Player::A)[1], 2);
         assert_eq!(child.store(Player::A), 0);
@@ -294,12 +1741,9 @@ mod tests {
 
     #[test]
     fn skip_opponents_store_on_sow() {
-        let mut s = State {
-            pits: [[1; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][0] = 14;
+        let mut pits = [[1; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][0] = 14;
+        let s = State::from_parts(pits, [0, 0], Player::A);
         let before_b = s.store(Player::B);
         let t_before = total(&s);
         let child = s.child_after_move(0).unwrap();
@@ -311,17 +1755,35 @@ mod tests {
 
     #[test]
     fn wraparound_skips_opponents_store_and_preserves_total() {
-        let mut s = State {
-            pits: [[1; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][5] = 20;
+        let mut pits = [[1; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][5] = 20;
+        let s = State::from_parts(pits, [0, 0], Player::A);
         let t_before = total(&s);
         let before_b = s.store(Player::B);
         let child = s.child_after_move(5).unwrap();
         assert_eq!(child.store(Player::B), before_b);
         assert_eq!(total(&child), t_before);
+        assert_eq!(s.move_wraps_around(5), Some(true));
+    }
+
+    #[test]
+    fn move_wraps_around_at_the_lap_boundary() {
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[0][5] = 7;
+        pits[0][2] = 10;
+        pits[1][0] = 1;
+        let s = State::from_parts(pits, [0, 0], Player::A);
+        // pit 5: store + six opponent pits, the eighth stone lands on pit 0
+        assert_eq!(s.move_wraps_around(5), Some(false));
+        assert_eq!(s.move_wraps_around(2), Some(false));
+        assert_eq!(s.move_wraps_around(0), None);
+        assert_eq!(s.move_wraps_around(PITS_PER_SIDE), None);
+
+        pits[0][5] = 8;
+        pits[0][2] = 11;
+        let s = State::from_parts(pits, [0, 0], Player::A);
+        assert_eq!(s.move_wraps_around(5), Some(true));
+        assert_eq!(s.move_wraps_around(2), Some(true));
     }
 
     #[test]
@@ -339,13 +1801,10 @@ mod tests {
 
     #[test]
     fn terminal_sweep_when_side_becomes_empty() {
-        let mut s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
-        s.pits[Player::A.idx()][5] = 1;
-        s.pits[Player::B.idx()][5] = 1;
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][5] = 1;
+        pits[Player::B.idx()][5] = 1;
+        let s = State::from_parts(pits, [0, 0], Player::A);
         let child = s.child_after_move(5).unwrap();
         assert!(child.is_terminal());
         assert!(child.pits(Player::A).iter().all(|&x| x == 0));
@@ -355,11 +1814,7 @@ mod tests {
 
     #[test]
     fn legal_moves_empty_when_terminal() {
-        let s = State {
-            pits: [[0; PITS_PER_SIDE]; 2],
-            stores: [0, 0],
-            to_move: Player::A,
-        };
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [0, 0], Player::A);
         assert!(s.is_terminal());
         assert!(s.legal_moves().is_empty());
         assert!(s.legal_actions().is_empty());
@@ -383,4 +1838,440 @@ mod tests {
             assert_eq!(total(&s), t0);
         }
     }
+
+    #[test]
+    fn apply_random_move_is_legal_and_reproducible() {
+        let s = State::new();
+        let mut r1 = StdRng::seed_from_u64(42);
+        let mut r2 = StdRng::seed_from_u64(42);
+        let (m1, c1) = s.apply_random_move(&mut r1).unwrap();
+        let (m2, c2) = s.apply_random_move(&mut r2).unwrap();
+        assert!(s.legal_moves().contains(&m1));
+        assert!(s.child_after_move(m1).unwrap() == c1);
+        assert_eq!(m1, m2);
+        assert!(c1 == c2);
+    }
+
+    #[test]
+    fn apply_random_move_is_none_when_terminal() {
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [0, 0], Player::A);
+        let mut rng = StdRng::seed_from_u64(0);
+        assert!(s.apply_random_move(&mut rng).is_none());
+    }
+
+    #[test]
+    fn try_child_reports_game_over() {
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [0, 0], Player::A);
+        assert_eq!(s.try_child_after_move(0).err(), Some(Error::GameOver));
+    }
+
+    #[test]
+    fn try_child_reports_out_of_bounds() {
+        let s = State::new();
+        assert_eq!(
+            s.try_child_after_move(PITS_PER_SIDE).err(),
+            Some(Error::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn try_child_reports_illegal_move_on_empty_pit() {
+        let s = State::new().child_after_move(0).unwrap();
+        // B to move; B's pits are all full, so step to A's turn again
+        let s = s.child_after_move(0).unwrap();
+        assert_eq!(s.current_player(), Player::A);
+        assert_eq!(s.try_child_after_move(0).err(), Some(Error::IllegalMove));
+    }
+
+    #[test]
+    fn try_child_agrees_with_child_after_move() {
+        let s = State::new();
+        for i in 0..PITS_PER_SIDE {
+            assert!(s.try_child_after_move(i).ok() == s.child_after_move(i));
+        }
+    }
+
+    #[test]
+    fn incremental_hash_matches_recompute() {
+        let mut s = State::new();
+        assert_eq!(s.hash, s.compute_hash());
+        for i in 0..200 {
+            let moves = s.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            s = s.child_after_move(moves[(i * 7) % moves.len()]).unwrap();
+            assert_eq!(s.hash, s.compute_hash());
+        }
+        assert!(s.is_terminal());
+    }
+
+    #[test]
+    fn hash_distinguishes_side_to_move() {
+        let a = State::from_parts([[1; PITS_PER_SIDE]; 2], [0, 0], Player::A);
+        let b = State::from_parts([[1; PITS_PER_SIDE]; 2], [0, 0], Player::B);
+        assert_ne!(a.zobrist_hash(), b.zobrist_hash());
+    }
+
+    #[test]
+    fn extra_turn_bonus_is_added_to_store() {
+        let rules = Rules {
+            extra_turn_bonus: 2,
+            ..Rules::default()
+        };
+        let s = State::with_rules(rules);
+        let t0 = total(&s);
+        let child = s.child_after_move(2).unwrap();
+        assert_eq!(child.current_player(), Player::A);
+        assert_eq!(child.store(Player::A), 1 + 2);
+        assert_eq!(total(&child), t0 + 2);
+        assert_eq!(child.rules(), rules);
+    }
+
+    #[test]
+    fn extra_turn_bonus_is_capped_at_the_stone_limit() {
+        let mut s = State::from_parts([[0, 0, 0, 0, 2, 1], [1; PITS_PER_SIDE]], [50, 0], Player::A);
+        s.set_rules(Rules {
+            extra_turn_bonus: 200,
+            ..Rules::default()
+        });
+        let once = s.child_after_move(5).unwrap();
+        assert_eq!(once.current_player(), Player::A);
+        assert_eq!(total(&once), u8::MAX as u16);
+        let twice = once.child_after_move(4).unwrap();
+        assert_eq!(twice.current_player(), Player::A);
+        assert_eq!(twice.store(Player::A), 50 + 1 + 196 + 1);
+        assert_eq!(total(&twice), u8::MAX as u16);
+        assert_eq!(twice.validate(), Ok(()));
+    }
+
+    #[test]
+    fn extra_turn_bonus_total_grows_only_on_extra_turns() {
+        let rules = Rules {
+            extra_turn_bonus: 3,
+            ..Rules::default()
+        };
+        let mut s = State::with_rules(rules);
+        let mut expected = total(&s);
+        for i in 0..200 {
+            let moves = s.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            let who = s.current_player();
+            let next = s.child_after_move(moves[(i * 5) % moves.len()]).unwrap();
+            let landed_in_store = next.current_player() == who;
+            if landed_in_store {
+                expected += rules.extra_turn_bonus as u16;
+            }
+            assert_eq!(total(&next), expected);
+            s = next;
+        }
+    }
+
+    #[test]
+    fn validate_accepts_reachable_positions() {
+        let mut s = State::new();
+        for i in 0..200 {
+            assert_eq!(s.validate(), Ok(()));
+            let moves = s.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            s = s.child_after_move(moves[(i * 3) % moves.len()]).unwrap();
+        }
+    }
+
+    #[test]
+    fn validate_rejects_corrupt_positions() {
+        let overfull = State::from_parts([[20; PITS_PER_SIDE]; 2], [10, 10], Player::A);
+        assert_eq!(overfull.validate(), Err(InvalidState::TooManyStones(260)));
+
+        let full_store = State::from_parts([[1; PITS_PER_SIDE]; 2], [u8::MAX, 0], Player::B);
+        assert!(matches!(
+            full_store.validate(),
+            Err(InvalidState::TooManyStones(_))
+        ));
+
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][3] = 4;
+        let unswept = State::from_parts(pits, [0, 0], Player::B);
+        assert_eq!(unswept.validate(), Err(InvalidState::UnsweptTerminal));
+
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::B.idx()][0] = 1;
+        let unswept_b = State::from_parts(pits, [20, 20], Player::A);
+        assert_eq!(unswept_b.validate(), Err(InvalidState::UnsweptTerminal));
+    }
+
+    #[test]
+    fn preview_sweep_on_opening_and_terminal() {
+        let s = State::new();
+        let side = PITS_PER_SIDE as u8 * STONES_PER_PIT;
+        assert_eq!(s.preview_sweep(), (side, side));
+
+        let child = s.child_after_move(2).unwrap();
+        assert_eq!(child.preview_sweep(), (side, side));
+
+        let mut pits = [[0; PITS_PER_SIDE]; 2];
+        pits[Player::A.idx()][5] = 1;
+        pits[Player::B.idx()][5] = 1;
+        let end = State::from_parts(pits, [3, 4], Player::A)
+            .child_after_move(5)
+            .unwrap();
+        assert!(end.is_terminal());
+        assert_eq!(
+            end.preview_sweep(),
+            (end.store(Player::A), end.store(Player::B))
+        );
+    }
+
+    #[test]
+    fn legal_transitions_pair_moves_with_actions() {
+        let s = State::new();
+        let t = s.legal_transitions();
+        let moves: Vec<usize> = t.iter().map(|(m, _)| *m).collect();
+        assert_eq!(moves, s.legal_moves());
+        assert!(t.into_iter().map(|(_, c)| c).eq(s.legal_actions()));
+    }
+
+    #[test]
+    fn is_successor_of_reports_originating_pit() {
+        let s = State::new();
+        for m in s.legal_moves() {
+            let child = s.child_after_move(m).unwrap();
+            assert_eq!(child.is_successor_of(&s), Some(m));
+        }
+        let grandchild = s.child_after_move(0).unwrap().child_after_move(0).unwrap();
+        assert_eq!(grandchild.is_successor_of(&s), None);
+        assert_eq!(s.is_successor_of(&s), None);
+    }
+
+    #[test]
+    fn random_playout_stops_at_max_len() {
+        let mut rng = StdRng::seed_from_u64(5);
+        assert_eq!(random_playout(&State::new(), &mut rng, 0), Outcome::Ongoing);
+        assert_eq!(random_playout(&State::new(), &mut rng, 3), Outcome::Ongoing);
+        assert_eq!(random_playout_len(&State::new(), &mut rng, 3).1, 3);
+        for _ in 0..20 {
+            assert_ne!(
+                random_playout(&State::new(), &mut rng, 1000),
+                Outcome::Ongoing
+            );
+        }
+    }
+
+    #[test]
+    fn infer_move_recovers_opening_moves() {
+        let s = State::new();
+        for (m, child) in s.legal_transitions() {
+            assert_eq!(infer_move(&s, &child), Some(m));
+            assert_eq!(infer_move(&child, &s), None);
+        }
+    }
+
+    #[test]
+    fn mirror_is_an_involution_that_commutes_with_moves() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut s = State::new();
+        while let Some((_, next)) = s.apply_random_move(&mut rng) {
+            let m = s.mirror();
+            assert!(m.mirror() == s);
+            assert_eq!(m.current_player(), s.current_player().opponent());
+            assert_eq!(m.pits(Player::A), s.pits(Player::B));
+            assert_eq!(m.store(Player::B), s.store(Player::A));
+            assert_eq!(m.legal_moves(), s.legal_moves());
+            for (a, child) in s.legal_transitions() {
+                assert!(m.child_after_move(a).unwrap() == child.mirror());
+            }
+            s = next;
+        }
+        assert!(s.mirror().is_terminal());
+    }
+
+    #[test]
+    fn structural_metrics_on_opening() {
+        let s = State::new();
+        assert_eq!(s.min_pit_count(Player::A), STONES_PER_PIT);
+        assert_eq!(s.min_pit_count(Player::B), STONES_PER_PIT);
+        assert_eq!(s.moves_to_empty_estimate(), PITS_PER_SIDE as u32);
+    }
+
+    #[test]
+    fn structural_metrics_on_near_empty_side() {
+        let mut a = [0; PITS_PER_SIDE];
+        a[PITS_PER_SIDE - 1] = 1;
+        let s = State::from_parts([a, [2; PITS_PER_SIDE]], [10, 10], Player::A);
+        assert_eq!(s.min_pit_count(Player::A), 0);
+        assert_eq!(s.min_pit_count(Player::B), 2);
+        assert_eq!(s.moves_to_empty_estimate(), 1);
+        // the bound is tight here: one move empties A's side
+        assert!(s.child_after_move(PITS_PER_SIDE - 1).unwrap().is_terminal());
+    }
+
+    #[test]
+    fn majority_store_decides_the_game() {
+        let mut b = [1; PITS_PER_SIDE];
+        b[0] = 5;
+        let s = State::from_parts([[2; PITS_PER_SIDE], b], [3, 26], Player::A);
+        assert_eq!(s.total_stones(), 51);
+        assert_eq!(s.is_decided(), Some(Outcome::Win(Player::B)));
+        assert!(!s.is_terminal());
+
+        let mut with_bonus = s.clone();
+        with_bonus.set_rules(Rules {
+            extra_turn_bonus: 1,
+            ..Rules::default()
+        });
+        assert_eq!(with_bonus.is_decided(), None);
+    }
+
+    #[test]
+    fn exactly_half_is_not_decided() {
+        let s = State::from_parts([[2; PITS_PER_SIDE], [1; PITS_PER_SIDE]], [1, 19], Player::A);
+        assert_eq!(s.total_stones(), 38);
+        assert_eq!(s.is_decided(), None);
+        assert_eq!(State::new().is_decided(), None);
+    }
+
+    #[test]
+    fn terminal_positions_are_decided() {
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [24, 24], Player::B);
+        assert_eq!(s.is_decided(), Some(Outcome::Draw));
+    }
+
+    #[test]
+    fn projected_score_matches_final_score_when_terminal() {
+        let s = State::from_parts([[0; PITS_PER_SIDE]; 2], [20, 28], Player::A);
+        assert_eq!(s.projected_score_for(Player::A), s.score_for(Player::A));
+        assert_eq!(s.projected_score_for(Player::B), 8);
+    }
+
+    #[test]
+    fn move_scores_on_opening_favor_extra_turn_pit() {
+        let s = State::new();
+        let scores = s.move_scores();
+        assert_eq!(
+            scores.iter().map(|&(m, _)| m).collect::<Vec<_>>(),
+            s.legal_moves()
+        );
+        let best = scores.iter().map(|&(_, v)| v).max().unwrap();
+        // pit 2 ends in the store and keeps every stone on A's side
+        assert_eq!(scores[2], (2, best));
+        assert!(scores[3..].iter().all(|&(_, v)| v < best));
+        // each stone sown onto B's side swings the projection by two
+        assert_eq!(scores[5], (5, -6));
+    }
+
+    fn feeding() -> Rules {
+        Rules {
+            feeding_required: true,
+            ..Rules::default()
+        }
+    }
+
+    /// B to move with only pit 5 (2 stones): it sows B's store and A's pit
+    /// 0, leaving B's side empty.
+    fn b_empties_side(a: [u8; PITS_PER_SIDE], rules: Rules) -> State {
+        let mut b = [0; PITS_PER_SIDE];
+        b[PITS_PER_SIDE - 1] = 2;
+        let mut s = State::from_parts([a, b], [10, 10], Player::B);
+        s.set_rules(rules);
+        s.child_after_move(PITS_PER_SIDE - 1).unwrap()
+    }
+
+    #[test]
+    fn feeding_restricts_moves_to_those_reaching_opponent() {
+        let mut a = [0; PITS_PER_SIDE];
+        a[PITS_PER_SIDE - 2] = 3;
+        let s = b_empties_side(a, feeding());
+        assert!(!s.is_terminal());
+        assert_eq!(s.validate(), Ok(()));
+        assert_eq!(s.current_player(), Player::A);
+        // pit 0 (1 stone) stays home; pit 4 (3 stones) reaches B's pit 0
+        assert_eq!(s.pits(Player::A)[0], 1);
+        assert_eq!(s.legal_moves(), vec![PITS_PER_SIDE - 2]);
+        let err = s.try_child_after_move(0).err();
+        assert_eq!(err, Some(Error::IllegalMove));
+        assert_eq!(err.unwrap().to_string(), "move is not legal here");
+        let fed = s.child_after_move(PITS_PER_SIDE - 2).unwrap();
+        assert_eq!(fed.pits(Player::B)[0], 1);
+        assert!(!fed.is_terminal());
+
+        // the standard game just ends
+        assert!(b_empties_side(a, Rules::default()).is_terminal());
+    }
+
+    #[test]
+    fn feeding_impossible_ends_with_sweep() {
+        let mut a = [0; PITS_PER_SIDE];
+        a[0] = 1;
+        let s = b_empties_side(a, feeding());
+        assert!(s.is_terminal());
+        assert_eq!(s.store(Player::A), 12);
+        assert_eq!(s.store(Player::B), 11);
+    }
+
+    #[test]
+    fn extra_turn_moves_on_opening() {
+        let s = State::new();
+        assert_eq!(
+            s.extra_turn_moves(),
+            vec![PITS_PER_SIDE - STONES_PER_PIT as usize]
+        );
+        assert!(!s.move_gives_extra_turn(0));
+        assert!(!s.move_gives_extra_turn(PITS_PER_SIDE));
+    }
+
+    #[test]
+    fn move_gives_extra_turn_matches_play() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let mut s = State::new();
+            while let Some((_, next)) = s.apply_random_move(&mut rng) {
+                for (m, child) in s.legal_transitions() {
+                    let kept = child.current_player() == s.current_player();
+                    assert_eq!(s.move_gives_extra_turn(m), kept, "{s:?} pit {m}");
+                }
+                s = next;
+            }
+        }
+        // a full lap plus the distance to the store
+        let mut a = [0; PITS_PER_SIDE];
+        a[0] = (PITS_PER_SIDE + 2 * PITS_PER_SIDE + 1) as u8;
+        let lap = State::from_parts([a, [1; PITS_PER_SIDE]], [0, 0], Player::A);
+        assert_eq!(lap.extra_turn_moves(), vec![0]);
+    }
+
+    #[test]
+    fn ring_positions_are_contiguous() {
+        let mut ring = Vec::new();
+        for side in Player::both() {
+            ring.extend((0..PITS_PER_SIDE).map(|i| State::ring_position(side, i)));
+            ring.push(State::store_ring_position(side));
+        }
+        assert_eq!(ring, (0..State::RING_LEN).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn sowing_skips_opponent_store_on_the_ring() {
+        // A's last pit with enough stones to pass B's store: A store, B's
+        // pits, then straight to A's pit 0.
+        let last = PITS_PER_SIDE - 1;
+        let mut a = [0; PITS_PER_SIDE];
+        a[0] = 1; // occupied, so the landing stone does not capture
+        a[last] = (PITS_PER_SIDE + 2) as u8;
+        let s = State::from_parts([a, [1; PITS_PER_SIDE]], [0, 0], Player::A);
+        let child = s.child_after_move(last).unwrap();
+        assert_eq!(child.store(Player::B), 0);
+        assert_eq!(child.pits(Player::A)[0], 2);
+
+        let b_store = State::store_ring_position(Player::B);
+        let after_b = State::ring_position(Player::B, last) + 1;
+        assert_eq!(after_b, b_store);
+        assert_eq!(
+            (b_store + 1) % State::RING_LEN,
+            State::ring_position(Player::A, 0)
+        );
+    }
 }
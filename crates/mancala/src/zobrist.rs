@@ -0,0 +1,41 @@
+//! Zobrist-style keys for incremental position hashing.
+//!
+//! Keys are derived on demand from a cell id and its stone count, so any
+//! count a `u8` can hold has a key without a precomputed table.
+
+use crate::{PITS_PER_SIDE, Player};
+
+/// Toggled in when `Player::B` is to move.
+const SIDE_TO_MOVE_KEY: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// splitmix64 finalizer.
+#[inline]
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[inline]
+fn cell_key(cell: usize, stones: u8) -> u64 {
+    mix(((cell as u64) << 8) | stones as u64)
+}
+
+#[inline]
+pub(crate) fn pit_key(side: usize, idx: usize, stones: u8) -> u64 {
+    cell_key(side * PITS_PER_SIDE + idx, stones)
+}
+
+#[inline]
+pub(crate) fn store_key(side: usize, stones: u8) -> u64 {
+    cell_key(2 * PITS_PER_SIDE + side, stones)
+}
+
+#[inline]
+pub(crate) fn side_key(to_move: Player) -> u64 {
+    match to_move {
+        Player::A => 0,
+        Player::B => SIDE_TO_MOVE_KEY,
+    }
+}